@@ -0,0 +1,116 @@
+use std::path::PathBuf;
+
+use kidl_db::{
+    diagnostics::{file_diagnostics, Diagnostic, Severity},
+    manifest::Project,
+    vendor::{VendorCache, LOCKFILE},
+    Database, Db,
+};
+
+#[derive(clap::Parser, Debug)]
+pub struct CheckCommand {
+    /// Schema files to check, defaults to all files of the project
+    files: Vec<PathBuf>,
+
+    /// Directory to look for the `kidl.toml` manifest from, defaults to
+    /// the current directory. Without a manifest, module paths in `use`
+    /// items start from this directory.
+    #[arg(long)]
+    root: Option<PathBuf>,
+}
+
+pub fn run(command: CheckCommand) -> bool {
+    let cwd = std::env::current_dir().unwrap_or_default();
+    let project = match Project::discover(&cwd.join(command.root.unwrap_or_default())) {
+        Ok(project) => project,
+        Err(e) => {
+            eprintln!("{}", e);
+            return false;
+        }
+    };
+
+    let cache = match VendorCache::open(&project.root) {
+        Ok(cache) => cache,
+        Err(e) => {
+            eprintln!("{}: {}", project.root.join(LOCKFILE).display(), e);
+            return false;
+        }
+    };
+
+    for problem in cache.problems() {
+        eprintln!("warning: {}", problem);
+    }
+
+    let mut db = Database::new(project.root.clone()).with_import_resolver(cache);
+    let (project_files, errors) = match db.load_project(&project) {
+        Ok(loaded) => loaded,
+        Err(e) => {
+            eprintln!("{}", e);
+            return false;
+        }
+    };
+
+    // The other files are still checked
+    let mut ok = errors.is_empty();
+    for e in errors {
+        eprintln!("{}", e);
+    }
+
+    let mut files = Vec::new();
+    for path in command.files.iter().map(|path| cwd.join(path)) {
+        // Files outside of the project aren't loaded yet
+        if db.schema_file(&path).is_none() {
+            match std::fs::read_to_string(&path) {
+                Ok(source) => db.push_file(path.clone(), source),
+                Err(e) => {
+                    eprintln!("{}: {}", path.display(), e);
+                    return false;
+                }
+            }
+        }
+        files.extend(db.schema_file(&path));
+    }
+
+    if command.files.is_empty() {
+        files = project_files;
+    } else {
+        db.load_imports();
+    }
+
+    for file in files {
+        for diagnostic in file_diagnostics(&db, file) {
+            ok &= diagnostic.severity != Severity::Error;
+            print_diagnostic(&db, &diagnostic);
+        }
+    }
+
+    ok
+}
+
+pub(crate) fn print_diagnostic(db: &Database, diagnostic: &Diagnostic) {
+    let text = diagnostic.file.text(db);
+    let path = diagnostic.file.path(db);
+    let offset = diagnostic.range.start.0.min(text.len_bytes());
+    let line = text.byte_to_line(offset);
+    let column = text.byte_to_char(offset) - text.line_to_char(line);
+
+    let severity = match diagnostic.severity {
+        Severity::Error => "error",
+        Severity::Warning => "warning",
+        Severity::Hint => "hint",
+    };
+
+    let severity = match diagnostic.code {
+        Some(code) => format!("{}[{}]", severity, code),
+        None => severity.to_owned(),
+    };
+
+    println!(
+        "{}:{}:{}: {}: {}",
+        db.workspace().display_path(db, path),
+        line + 1,
+        column + 1,
+        severity,
+        diagnostic.message
+    );
+}
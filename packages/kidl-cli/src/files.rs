@@ -0,0 +1,28 @@
+use std::path::{Path, PathBuf};
+
+/// Expands directories into the schema files they contain.
+pub fn collect(paths: &[PathBuf]) -> std::io::Result<Vec<PathBuf>> {
+    let mut files = Vec::new();
+    for path in paths {
+        visit(path, &mut files)?;
+    }
+
+    files.sort();
+    files.dedup();
+    Ok(files)
+}
+
+fn visit(path: &Path, files: &mut Vec<PathBuf>) -> std::io::Result<()> {
+    if path.is_dir() {
+        for entry in std::fs::read_dir(path)? {
+            let path = entry?.path();
+            if path.is_dir() || path.extension().map_or(false, |e| e == "kidl") {
+                visit(&path, files)?;
+            }
+        }
+    } else {
+        files.push(path.to_owned());
+    }
+
+    Ok(())
+}
@@ -0,0 +1,109 @@
+use std::path::PathBuf;
+
+use kidl_db::manifest::Project;
+use kidl_syn::format::{format_str, FormatConfig, TrailingCommas};
+
+#[derive(clap::Parser, Debug)]
+pub struct FormatCommand {
+    /// Files or directories to format
+    #[arg(required = true)]
+    paths: Vec<PathBuf>,
+
+    /// Don't write files, fail if any of them is not formatted
+    #[arg(long)]
+    check: bool,
+
+    #[arg(long, default_value_t = 4)]
+    indent_width: usize,
+
+    /// Indent with tabs, each counts as `--indent-width` columns
+    #[arg(long)]
+    use_tabs: bool,
+
+    #[arg(long, default_value_t = 100)]
+    max_width: usize,
+
+    #[arg(long, value_enum, default_value_t = TrailingCommasArg::Always)]
+    trailing_commas: TrailingCommasArg,
+}
+
+#[derive(clap::ValueEnum, Clone, Copy, Debug)]
+enum TrailingCommasArg {
+    Always,
+    Never,
+}
+
+impl FormatCommand {
+    /// Fields are separated as the manifest of the project in the current
+    /// directory says.
+    fn config(&self, project: &Project) -> FormatConfig {
+        FormatConfig {
+            indent_width: self.indent_width,
+            use_tabs: self.use_tabs,
+            max_width: self.max_width,
+            trailing_commas: match self.trailing_commas {
+                TrailingCommasArg::Always => TrailingCommas::Always,
+                TrailingCommasArg::Never => TrailingCommas::Never,
+            },
+            field_separators: project.manifest.syntax.parse_options().field_separators,
+        }
+    }
+}
+
+pub fn run(command: FormatCommand) -> bool {
+    let cwd = std::env::current_dir().unwrap_or_default();
+    let config = match Project::discover(&cwd) {
+        Ok(project) => command.config(&project),
+        Err(e) => {
+            eprintln!("{}", e);
+            return false;
+        }
+    };
+
+    let files = match crate::files::collect(&command.paths) {
+        Ok(files) => files,
+        Err(e) => {
+            eprintln!("{}", e);
+            return false;
+        }
+    };
+
+    let mut ok = true;
+    for path in files {
+        let source = match std::fs::read_to_string(&path) {
+            Ok(source) => source,
+            Err(e) => {
+                eprintln!("{}: {}", path.display(), e);
+                ok = false;
+                continue;
+            }
+        };
+
+        let formatted = match format_str(&source, &config) {
+            Ok(formatted) => formatted,
+            Err(errors) => {
+                eprintln!(
+                    "{}: skipped, the file has {} syntax error(s)",
+                    path.display(),
+                    errors.len()
+                );
+                ok = false;
+                continue;
+            }
+        };
+
+        if formatted == source {
+            continue;
+        }
+
+        if command.check {
+            println!("{}: not formatted", path.display());
+            ok = false;
+        } else if let Err(e) = std::fs::write(&path, formatted) {
+            eprintln!("{}: {}", path.display(), e);
+            ok = false;
+        }
+    }
+
+    ok
+}
@@ -1,16 +1,26 @@
+use std::process::ExitCode;
+
 use clap::Parser;
 use tracing_subscriber::util::SubscriberInitExt;
 
+mod check;
+mod files;
+mod fmt;
+mod vendor;
+
 #[derive(Parser, Debug)]
 #[command(author, version, about, long_about = None)]
 enum Args {
     LSP(LanguageServerCommand),
+    Check(check::CheckCommand),
+    Fmt(fmt::FormatCommand),
+    Vendor(vendor::VendorCommand),
 }
 
 #[derive(Parser, Debug)]
 struct LanguageServerCommand {}
 
-fn main() {
+fn main() -> ExitCode {
     let _ = tracing_subscriber::fmt()
         .compact()
         .with_writer(std::io::stderr)
@@ -23,5 +33,22 @@ fn main() {
         Args::LSP(_server) => {
             let _ = kidl_lsp::start();
         }
+        Args::Check(command) => {
+            if !check::run(command) {
+                return ExitCode::FAILURE;
+            }
+        }
+        Args::Fmt(command) => {
+            if !fmt::run(command) {
+                return ExitCode::FAILURE;
+            }
+        }
+        Args::Vendor(command) => {
+            if !vendor::run(command) {
+                return ExitCode::FAILURE;
+            }
+        }
     }
+
+    ExitCode::SUCCESS
 }
@@ -0,0 +1,130 @@
+use std::{collections::BTreeSet, io::Read, path::PathBuf};
+
+use kidl_db::{
+    imports::module_url,
+    ir::lower_file,
+    manifest::Project,
+    vendor::{VendorCache, LOCKFILE},
+    Database,
+};
+
+#[derive(clap::Parser, Debug)]
+pub struct VendorCommand {
+    /// Files or directories whose URL imports are downloaded, defaults to
+    /// all files of the project
+    paths: Vec<PathBuf>,
+
+    /// Directory to look for the `kidl.toml` manifest from, defaults to
+    /// the current directory. The project directory holds the lockfile and
+    /// the vendor directory.
+    #[arg(long)]
+    root: Option<PathBuf>,
+
+    /// Download imports again even if they are already vendored, and
+    /// accept contents that changed since they were locked
+    #[arg(long)]
+    update: bool,
+}
+
+pub fn run(command: VendorCommand) -> bool {
+    let cwd = std::env::current_dir().unwrap_or_default();
+    let project = match Project::discover(&cwd.join(command.root.unwrap_or_default())) {
+        Ok(project) => project,
+        Err(e) => {
+            eprintln!("{}", e);
+            return false;
+        }
+    };
+
+    let mut cache = match VendorCache::open(&project.root) {
+        Ok(cache) => cache,
+        Err(e) => {
+            eprintln!("{}: {}", project.root.join(LOCKFILE).display(), e);
+            return false;
+        }
+    };
+
+    let mut ok = true;
+    let files = match command.paths.is_empty() {
+        true => project
+            .files()
+            .map_err(|e| e.to_string())
+            .map(|(files, errors)| {
+                // The imports of the other files are still downloaded
+                for e in &errors {
+                    eprintln!("{}", e);
+                }
+                ok = errors.is_empty();
+                files
+            }),
+        false => {
+            let paths = command
+                .paths
+                .iter()
+                .map(|p| cwd.join(p))
+                .collect::<Vec<_>>();
+            crate::files::collect(&paths).map_err(|e| e.to_string())
+        }
+    };
+
+    let files = match files {
+        Ok(files) => files,
+        Err(e) => {
+            eprintln!("{}", e);
+            return false;
+        }
+    };
+
+    let mut db = Database::new(project.root);
+    for path in files {
+        match std::fs::read_to_string(&path) {
+            Ok(source) => db.push_file(path, source),
+            Err(e) => {
+                eprintln!("{}: {}", path.display(), e);
+                ok = false;
+            }
+        }
+    }
+
+    let urls = db
+        .schema_files()
+        .into_iter()
+        .flat_map(|file| lower_file(&db, file).uses(&db).clone())
+        .filter_map(|item| module_url(&db, item).map(str::to_owned))
+        .collect::<BTreeSet<_>>();
+
+    for url in urls {
+        if cache.is_vendored(&url) && !command.update {
+            continue;
+        }
+
+        // Without `--update`, downloads have to match the lockfile
+        let stored = download(&url).and_then(|contents| match command.update {
+            true => Ok(cache.update(&url, &contents)?),
+            false => Ok(cache.store(&url, &contents)?),
+        });
+        match stored {
+            Ok(path) => println!("{} -> {}", url, path.display()),
+            Err(e) => {
+                eprintln!("{}: {}", url, e);
+                ok = false;
+            }
+        }
+    }
+
+    if let Err(e) = cache.save() {
+        eprintln!("{}: {}", LOCKFILE, e);
+        return false;
+    }
+
+    ok
+}
+
+fn download(url: &str) -> Result<Vec<u8>, Box<dyn std::error::Error>> {
+    let mut contents = Vec::new();
+    ureq::get(url)
+        .call()?
+        .into_reader()
+        .read_to_end(&mut contents)?;
+    Ok(contents)
+}
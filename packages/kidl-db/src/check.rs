@@ -0,0 +1,15 @@
+//! Entry point for all checks of a file.
+
+use crate::ir::SchemaFile;
+
+/// Runs every check of `file`. The results are read back from the
+/// [`crate::diagnostics::Diagnostics`] accumulator.
+#[salsa::tracked]
+pub fn check_file(db: &dyn crate::Db, file: SchemaFile) {
+    crate::imports::check_imports(db, file);
+    crate::resolve::file_scope(db, file);
+    crate::resolve::check_names(db, file);
+    crate::resolve::check_unused_imports(db, file);
+    crate::typeck::check_types(db, file);
+    crate::cycles::check_cycles(db, file);
+}
@@ -0,0 +1,376 @@
+//! Recursive types.
+//!
+//! A struct contains another one by value when a field's type resolves to
+//! it, directly or through aliases and `Option`. `List`, `Map` and `Set`
+//! store their elements on the heap and break containment. Structs that
+//! contain each other form a recursive component; generators box one of
+//! the fields in each of them. A cycle made only of required fields has
+//! no finite value at all and is reported as an error.
+
+use std::collections::{HashMap, HashSet, VecDeque};
+
+use crate::{
+    diagnostics::{Code, Diagnostic, Diagnostics},
+    imports::imported_item,
+    ir::{lower_file, FieldDef, Item, SchemaFile, StructDef, TypeRef},
+    prelude::Builtin,
+    resolve::{resolve_type_ref, Resolution},
+    workspace::Workspace,
+};
+
+/// Aliases that refer to each other are cut off at this depth.
+pub(crate) const MAX_ALIAS_DEPTH: usize = 32;
+
+/// A field of `from` that contains `to` by value.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub struct Edge {
+    pub from: StructDef,
+    pub field: FieldDef,
+    pub to: StructDef,
+    /// The field is required and `to` isn't wrapped in an `Option`.
+    pub required: bool,
+}
+
+/// Containment between structs, see [`type_graph`] and [`file_type_graph`].
+#[derive(Debug, Clone, PartialEq, Eq, Default)]
+pub struct TypeGraph {
+    edges: Vec<Edge>,
+    /// Strongly connected components with a cycle, using all edges.
+    recursive: Vec<Vec<StructDef>>,
+    /// Strongly connected components with a cycle, using required edges.
+    infinite: Vec<Vec<StructDef>>,
+}
+
+impl TypeGraph {
+    fn new(structs: &[StructDef], edges: Vec<Edge>) -> TypeGraph {
+        let recursive = recursive_components(structs, &edges, |_| true);
+        let infinite = recursive_components(structs, &edges, |edge| edge.required);
+
+        TypeGraph {
+            edges,
+            recursive,
+            infinite,
+        }
+    }
+
+    pub fn edges(&self) -> &[Edge] {
+        &self.edges
+    }
+
+    pub fn edges_from(&self, def: StructDef) -> impl Iterator<Item = &Edge> + '_ {
+        self.edges.iter().filter(move |edge| edge.from == def)
+    }
+
+    /// Groups of structs that contain each other, directly or through
+    /// optional fields. Each of them needs at least one boxed field.
+    pub fn recursive_components(&self) -> &[Vec<StructDef>] {
+        &self.recursive
+    }
+
+    /// Groups of structs that contain each other through required fields
+    /// only, they have infinite size.
+    pub fn infinite_components(&self) -> &[Vec<StructDef>] {
+        &self.infinite
+    }
+
+    pub fn component(&self, def: StructDef) -> Option<&[StructDef]> {
+        self.recursive
+            .iter()
+            .find(|component| component.contains(&def))
+            .map(Vec::as_slice)
+    }
+
+    /// Whether `edge` stays within a recursive component, i.e. the field
+    /// has to be boxed by generators that lay out structs inline.
+    pub fn is_recursive(&self, edge: &Edge) -> bool {
+        self.component(edge.from)
+            .is_some_and(|component| component.contains(&edge.to))
+    }
+
+    /// Shortest cycle of required fields from `def` back to itself.
+    fn infinite_cycle(&self, def: StructDef) -> Option<Vec<Edge>> {
+        let component = self.infinite.iter().find(|c| c.contains(&def))?;
+        let mut parents: HashMap<StructDef, Edge> = HashMap::new();
+        let mut queue = VecDeque::from([def]);
+
+        while let Some(current) = queue.pop_front() {
+            for edge in self.edges_from(current) {
+                if !edge.required || !component.contains(&edge.to) {
+                    continue;
+                }
+
+                if edge.to == def {
+                    let mut cycle = vec![*edge];
+                    let mut current = edge.from;
+                    while current != def {
+                        let edge = parents[&current];
+                        cycle.push(edge);
+                        current = edge.from;
+                    }
+                    cycle.reverse();
+                    return Some(cycle);
+                }
+
+                if !parents.contains_key(&edge.to) {
+                    parents.insert(edge.to, *edge);
+                    queue.push_back(edge.to);
+                }
+            }
+        }
+
+        None
+    }
+}
+
+/// Containment between all structs of the workspace, for generators.
+#[salsa::tracked(return_ref)]
+pub fn type_graph(db: &dyn crate::Db, workspace: Workspace) -> TypeGraph {
+    let structs = workspace
+        .files(db)
+        .values()
+        .flat_map(|file| lower_file(db, *file).structs(db).clone())
+        .collect::<Vec<_>>();
+
+    let edges = structs
+        .iter()
+        .flat_map(|def| struct_edges(db, *def).iter().copied())
+        .collect();
+    TypeGraph::new(&structs, edges)
+}
+
+/// Containment between the structs of `file` and all structs they contain,
+/// directly or not. Every cycle through a struct of `file` stays within
+/// these, so unlike [`type_graph`] the graph only changes with the files
+/// that `file` reaches.
+#[salsa::tracked(return_ref)]
+pub fn file_type_graph(db: &dyn crate::Db, file: SchemaFile) -> TypeGraph {
+    let mut structs = lower_file(db, file).structs(db).clone();
+    let mut seen = structs.iter().copied().collect::<HashSet<_>>();
+    let mut edges = Vec::new();
+
+    let mut next = 0;
+    while let Some(def) = structs.get(next).copied() {
+        next += 1;
+        for edge in struct_edges(db, def) {
+            edges.push(*edge);
+            if seen.insert(edge.to) {
+                structs.push(edge.to);
+            }
+        }
+    }
+
+    TypeGraph::new(&structs, edges)
+}
+
+/// Structs that the fields of `def` contain by value.
+#[salsa::tracked(return_ref)]
+pub fn struct_edges(db: &dyn crate::Db, def: StructDef) -> Vec<Edge> {
+    let mut edges = Vec::new();
+    for field in def.fields(db) {
+        let Some(ty) = field.ty(db) else {
+            continue;
+        };
+
+        let mut contained = Vec::new();
+        collect_contained(db, def.file(db), ty, !field.optional(db), 0, &mut contained);
+        edges.extend(contained.into_iter().map(|(to, required)| Edge {
+            from: def,
+            field: *field,
+            to,
+            required,
+        }));
+    }
+    edges
+}
+
+/// Structs that `ty`, written in `file`, contains by value.
+fn collect_contained(
+    db: &dyn crate::Db,
+    file: SchemaFile,
+    ty: &TypeRef,
+    required: bool,
+    depth: usize,
+    contained: &mut Vec<(StructDef, bool)>,
+) {
+    let item = match resolve_type_ref(db, file, ty.name) {
+        Some(Resolution::Builtin(Builtin::Option)) => {
+            for arg in &ty.args {
+                collect_contained(db, file, arg, false, depth, contained);
+            }
+            return;
+        }
+        Some(Resolution::Builtin(_)) | None => return,
+        Some(Resolution::Local(item)) => item,
+        Some(Resolution::Import(item)) => match imported_item(db, item) {
+            Some(item) => item,
+            None => return,
+        },
+    };
+
+    match item {
+        Item::Struct(def) => contained.push((def, required)),
+        Item::Alias(alias) if depth < MAX_ALIAS_DEPTH => {
+            if let Some(ty) = alias.ty(db) {
+                collect_contained(db, alias.file(db), ty, required, depth + 1, contained);
+            }
+        }
+        Item::Alias(_) | Item::Enum(_) => {}
+    }
+}
+
+/// Strongly connected components of the graph made of `edges` that pass
+/// `filter`, keeping only those that have a cycle. Tarjan's algorithm.
+fn recursive_components(
+    structs: &[StructDef],
+    edges: &[Edge],
+    filter: impl Fn(&Edge) -> bool,
+) -> Vec<Vec<StructDef>> {
+    let index_of = structs
+        .iter()
+        .enumerate()
+        .map(|(index, def)| (*def, index))
+        .collect::<HashMap<_, _>>();
+
+    let mut successors = vec![Vec::new(); structs.len()];
+    for edge in edges.iter().filter(|edge| filter(edge)) {
+        if let (Some(from), Some(to)) = (index_of.get(&edge.from), index_of.get(&edge.to)) {
+            successors[*from].push(*to);
+        }
+    }
+
+    let mut tarjan = Tarjan {
+        successors: &successors,
+        index: vec![None; structs.len()],
+        low_link: vec![0; structs.len()],
+        on_stack: vec![false; structs.len()],
+        stack: Vec::new(),
+        next_index: 0,
+        components: Vec::new(),
+    };
+
+    for node in 0..structs.len() {
+        if tarjan.index[node].is_none() {
+            tarjan.visit(node);
+        }
+    }
+
+    tarjan
+        .components
+        .into_iter()
+        .filter(|component| match component.as_slice() {
+            [node] => successors[*node].contains(node),
+            _ => true,
+        })
+        .map(|component| component.into_iter().map(|node| structs[node]).collect())
+        .collect()
+}
+
+struct Tarjan<'a> {
+    successors: &'a [Vec<usize>],
+    index: Vec<Option<usize>>,
+    low_link: Vec<usize>,
+    on_stack: Vec<bool>,
+    stack: Vec<usize>,
+    next_index: usize,
+    components: Vec<Vec<usize>>,
+}
+
+impl Tarjan<'_> {
+    /// Visits everything reachable from `root`. The calls of the recursive
+    /// formulation are kept on an explicit stack, together with the next
+    /// successor to look at, so that long chains of structs can't overflow
+    /// the thread's stack.
+    fn visit(&mut self, root: usize) {
+        self.open(root);
+        let mut calls = vec![(root, 0)];
+
+        while let Some((node, position)) = calls.last_mut() {
+            let node = *node;
+            if let Some(&next) = self.successors[node].get(*position) {
+                *position += 1;
+                match self.index[next] {
+                    None => {
+                        self.open(next);
+                        calls.push((next, 0));
+                    }
+                    Some(index) if self.on_stack[next] => {
+                        self.low_link[node] = self.low_link[node].min(index);
+                    }
+                    Some(_) => {}
+                }
+                continue;
+            }
+
+            calls.pop();
+            if let Some((parent, _)) = calls.last() {
+                self.low_link[*parent] = self.low_link[*parent].min(self.low_link[node]);
+            }
+
+            if Some(self.low_link[node]) == self.index[node] {
+                let mut component = Vec::new();
+                while let Some(member) = self.stack.pop() {
+                    self.on_stack[member] = false;
+                    component.push(member);
+                    if member == node {
+                        break;
+                    }
+                }
+                component.reverse();
+                self.components.push(component);
+            }
+        }
+    }
+
+    fn open(&mut self, node: usize) {
+        self.index[node] = Some(self.next_index);
+        self.low_link[node] = self.next_index;
+        self.next_index += 1;
+        self.stack.push(node);
+        self.on_stack[node] = true;
+    }
+}
+
+/// Reports the structs of `file` that have infinite size, with a hint on
+/// each field of the cycle that is declared in `file`.
+#[salsa::tracked]
+pub fn check_cycles(db: &dyn crate::Db, file: SchemaFile) {
+    let graph = file_type_graph(db, file);
+    for def in lower_file(db, file).structs(db) {
+        let Some(cycle) = graph.infinite_cycle(*def) else {
+            continue;
+        };
+
+        let path = cycle
+            .iter()
+            .map(|edge| {
+                format!(
+                    "{}.{}",
+                    edge.from.name(db).text(db),
+                    edge.field.name(db).text(db)
+                )
+            })
+            .chain(std::iter::once(def.name(db).text(db).clone()))
+            .collect::<Vec<_>>();
+
+        let message = format!(
+            "Struct `{}` has infinite size, it contains itself: {}",
+            def.name(db).text(db),
+            path.join(" -> ")
+        );
+        Diagnostics::push(
+            db,
+            Diagnostic::error(file, def.name_span(db), message).with_code(Code::InfiniteSize),
+        );
+
+        for edge in cycle.iter().filter(|edge| edge.from == *def) {
+            let message = format!(
+                "Make `{}` optional to break the cycle, it will be boxed in generated code",
+                edge.field.name(db).text(db)
+            );
+            Diagnostics::push(
+                db,
+                Diagnostic::hint(file, edge.field.span(db), message).with_code(Code::InfiniteSize),
+            );
+        }
+    }
+}
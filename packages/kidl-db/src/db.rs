@@ -1,32 +1,144 @@
 use std::{
-    collections::HashMap,
+    collections::{BTreeMap, BTreeSet},
     path::{Path, PathBuf},
+    sync::Arc,
 };
 
-use crate::{cache::Cache, ir::SchemaFile, Db};
+use kidl_syn::parser::ParseOptions;
+
+use crate::{
+    cache::Cache,
+    imports::module_path,
+    ir::{lower_file, SchemaFile},
+    manifest::{LoadError, Project},
+    vendor::{ImportResolver, NoUrlImports},
+    workspace::{normalize, Workspace},
+    Db,
+};
 
-#[derive(Default)]
 #[salsa::db(crate::Jar)]
 pub struct Database {
     storage: salsa::Storage<Self>,
     cache: Cache,
-    schema_files: HashMap<PathBuf, SchemaFile>,
+    workspace: Option<Workspace>,
+    import_resolver: Arc<dyn ImportResolver>,
 }
 
 impl Db for Database {
     fn cache(&self) -> Cache {
         self.cache.clone()
     }
+
+    fn workspace(&self) -> Workspace {
+        self.workspace
+            .expect("workspace is created with the database")
+    }
+
+    fn import_resolver(&self) -> &dyn ImportResolver {
+        &*self.import_resolver
+    }
+}
+
+impl Default for Database {
+    fn default() -> Self {
+        Database::new(PathBuf::new())
+    }
 }
 
 impl Database {
+    /// Creates a database for the project in `root`.
+    pub fn new(root: PathBuf) -> Self {
+        let mut db = Database {
+            storage: Default::default(),
+            cache: Default::default(),
+            workspace: None,
+            import_resolver: Arc::new(NoUrlImports),
+        };
+
+        db.workspace = Some(Workspace::new(
+            &db,
+            normalize(&root),
+            BTreeMap::new(),
+            ParseOptions::default(),
+            BTreeMap::new(),
+        ));
+        db
+    }
+
+    /// Sets the resolver of URL imports. This has to happen before any
+    /// query runs, the results of queries are not invalidated.
+    pub fn with_import_resolver(mut self, resolver: impl ImportResolver + 'static) -> Self {
+        self.import_resolver = Arc::new(resolver);
+        self
+    }
+
+    pub fn set_root(&mut self, root: PathBuf) {
+        self.workspace().set_root(self).to(normalize(&root));
+    }
+
+    pub fn set_aliases(&mut self, aliases: BTreeMap<String, PathBuf>) {
+        let aliases = aliases
+            .into_iter()
+            .map(|(name, path)| (name, normalize(&path)))
+            .collect();
+        self.workspace().set_aliases(self).to(aliases);
+    }
+
+    pub fn set_parse_options(&mut self, options: ParseOptions) {
+        self.workspace().set_parse_options(self).to(options);
+    }
+
+    /// Takes the root, the import aliases and the syntax options of
+    /// `project`, then reads all of its schema files and their imports.
+    /// Returns the schema files of the project, without the imports from
+    /// outside of it, and the files and directories that couldn't be read.
+    /// Those are skipped, only an invalid manifest fails the whole project.
+    pub fn load_project(
+        &mut self,
+        project: &Project,
+    ) -> Result<(Vec<SchemaFile>, Vec<LoadError>), LoadError> {
+        self.set_root(project.root.clone());
+        self.set_aliases(project.aliases());
+        self.set_parse_options(project.manifest.syntax.parse_options());
+
+        let (paths, mut errors) = project.files()?;
+        let mut files = Vec::new();
+        for path in paths {
+            match std::fs::read_to_string(&path) {
+                Ok(source) => {
+                    self.push_file(path.clone(), source);
+                    files.extend(self.schema_file(&path));
+                }
+                Err(error) => errors.push(LoadError { path, error }),
+            }
+        }
+
+        self.load_imports();
+        Ok((files, errors))
+    }
+
     pub fn schema_file(&self, path: &Path) -> Option<SchemaFile> {
-        self.schema_files.get(path).cloned()
+        self.workspace().file(self, &normalize(path))
     }
 
+    pub fn schema_files(&self) -> Vec<SchemaFile> {
+        self.workspace().files(self).values().copied().collect()
+    }
+
+    /// Adds a file, or replaces the text of a file that is already known.
     pub fn push_file(&mut self, path: PathBuf, source: String) {
-        let schema_file = SchemaFile::new(self, path.clone(), ropey::Rope::from(source));
-        self.schema_files.insert(path, schema_file);
+        let path = normalize(&path);
+        let text = ropey::Rope::from(source);
+        if let Some(schema_file) = self.schema_file(&path) {
+            schema_file.set_text(self).to(text);
+            return;
+        }
+
+        let workspace = self.workspace();
+        let schema_file = SchemaFile::new(self, path.clone(), text);
+        let mut files = workspace.files(self).clone();
+        files.insert(path, schema_file);
+        workspace.set_files(self).to(files);
     }
 
     pub fn push_files(&mut self, files: Vec<(PathBuf, String)>) {
@@ -34,6 +146,33 @@ impl Database {
             self.push_file(file.0, file.1)
         }
     }
+
+    /// Reads the files imported by known files from disk, until all
+    /// imports that point to existing files are loaded.
+    pub fn load_imports(&mut self) {
+        loop {
+            let workspace = self.workspace();
+            let missing = workspace
+                .files(self)
+                .values()
+                .flat_map(|file| lower_file(self, *file).uses(self))
+                .filter_map(|item| module_path(self, *item))
+                .filter(|path| workspace.file(self, path).is_none())
+                .collect::<BTreeSet<_>>();
+
+            let mut loaded = false;
+            for path in missing {
+                if let Ok(source) = std::fs::read_to_string(&path) {
+                    self.push_file(path, source);
+                    loaded = true;
+                }
+            }
+
+            if !loaded {
+                break;
+            }
+        }
+    }
 }
 
 impl salsa::Database for Database {}
@@ -42,7 +181,8 @@ impl salsa::ParallelDatabase for Database {
     fn snapshot(&self) -> salsa::Snapshot<Self> {
         salsa::Snapshot::new(Database {
             storage: self.storage.snapshot(),
-            schema_files: self.schema_files.clone(),
+            workspace: self.workspace,
+            import_resolver: self.import_resolver.clone(),
             cache: self.cache.clone(),
         })
     }
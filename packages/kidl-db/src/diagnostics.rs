@@ -0,0 +1,119 @@
+use std::{fmt, ops::Range};
+
+use kidl_syn::{
+    helpers::ByteOffset,
+    parser::{self, SyntaxError},
+};
+
+use crate::ir::SchemaFile;
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, PartialOrd, Ord, Hash)]
+pub enum Severity {
+    Error,
+    Warning,
+    Hint,
+}
+
+/// Stable identifier of a kind of diagnostic. The string form never
+/// changes, so editors and scripts can match on it.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, PartialOrd, Ord, Hash)]
+pub enum Code {
+    DuplicateDefinition,
+    AmbiguousImport,
+    ImportShadowsDeclaration,
+    UnusedImport,
+    NotExported,
+    InfiniteSize,
+}
+
+impl Code {
+    pub fn as_str(self) -> &'static str {
+        match self {
+            Code::DuplicateDefinition => "duplicate-definition",
+            Code::AmbiguousImport => "ambiguous-import",
+            Code::ImportShadowsDeclaration => "import-shadows-declaration",
+            Code::UnusedImport => "unused-import",
+            Code::NotExported => "not-exported",
+            Code::InfiniteSize => "infinite-size",
+        }
+    }
+}
+
+impl fmt::Display for Code {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.write_str(self.as_str())
+    }
+}
+
+#[derive(Debug, Clone, PartialEq, Eq, Hash)]
+pub struct Diagnostic {
+    pub file: SchemaFile,
+    pub range: Range<ByteOffset>,
+    pub severity: Severity,
+    pub code: Option<Code>,
+    pub message: String,
+}
+
+impl Diagnostic {
+    pub fn error(file: SchemaFile, range: Range<ByteOffset>, message: impl Into<String>) -> Self {
+        Diagnostic {
+            file,
+            range,
+            severity: Severity::Error,
+            code: None,
+            message: message.into(),
+        }
+    }
+
+    pub fn warning(file: SchemaFile, range: Range<ByteOffset>, message: impl Into<String>) -> Self {
+        Diagnostic {
+            severity: Severity::Warning,
+            ..Diagnostic::error(file, range, message)
+        }
+    }
+
+    pub fn hint(file: SchemaFile, range: Range<ByteOffset>, message: impl Into<String>) -> Self {
+        Diagnostic {
+            severity: Severity::Hint,
+            ..Diagnostic::error(file, range, message)
+        }
+    }
+
+    pub fn with_code(self, code: Code) -> Self {
+        Diagnostic {
+            code: Some(code),
+            ..self
+        }
+    }
+
+    pub fn from_syntax(file: SchemaFile, error: &SyntaxError) -> Self {
+        let severity = match error.severity() {
+            parser::Severity::Error => Severity::Error,
+            parser::Severity::Lint => Severity::Warning,
+        };
+
+        Diagnostic {
+            severity,
+            ..Diagnostic::error(file, error.range(), error.to_string())
+        }
+    }
+}
+
+/// Collects diagnostics produced by tracked queries. Use
+/// `query::accumulated::<Diagnostics>(db, file)` to read them back.
+#[salsa::accumulator]
+pub struct Diagnostics(Diagnostic);
+
+/// All diagnostics of a file: syntax errors and the results of
+/// [`crate::check::check_file`]. Checks look into imported files, what
+/// they report about those files is left out.
+pub fn file_diagnostics(db: &dyn crate::Db, file: SchemaFile) -> Vec<Diagnostic> {
+    let mut diagnostics = crate::check::check_file::accumulated::<Diagnostics>(db, file);
+    diagnostics.retain(|diagnostic| diagnostic.file == file);
+    diagnostics
+}
+
+/// Syntax errors of a file, as reported by the parser.
+pub fn syntax_diagnostics(db: &dyn crate::Db, file: SchemaFile) -> Vec<Diagnostic> {
+    crate::source::parse::accumulated::<Diagnostics>(db, file)
+}
@@ -0,0 +1,364 @@
+//! Module resolution for `use` items and the import graph.
+//!
+//! A module path is made of all segments but the last one, which names the
+//! imported item. Identifier segments are directories relative to the
+//! workspace root, with the last one naming a `.kidl` file: `use a::b::C`
+//! imports `C` from `a/b.kidl`. Quoted segments are taken verbatim and
+//! start relative to the importing file: `use "../c.kidl"::C`. A quoted
+//! URL is mapped to a local file by the [`crate::vendor::ImportResolver`]
+//! of the database. A first segment that is an import alias of the
+//! [`crate::manifest`] is replaced by the path it stands for.
+
+use std::{
+    collections::{HashMap, VecDeque},
+    path::{Component, Path, PathBuf},
+};
+
+use kidl_syn::parser::is_identifier;
+
+use crate::{
+    diagnostics::{Code, Diagnostic, Diagnostics},
+    ir::{lower_file, Item, Name, PathSegment, SchemaFile, Span, UseDef},
+    resolve::{file_scope, Resolution},
+    vendor::is_url,
+    workspace::{normalize, Workspace},
+};
+
+pub const EXTENSION: &str = "kidl";
+
+/// The URL `item` imports from, if any.
+pub fn module_url(db: &dyn crate::Db, item: UseDef) -> Option<&str> {
+    match item.path(db).first()? {
+        PathSegment::String(text, _) if is_url(text) => Some(text.as_str()),
+        _ => None,
+    }
+}
+
+/// The file `item` imports from. `None` when the path has no module part
+/// or points to an URL that isn't vendored.
+pub fn module_path(db: &dyn crate::Db, item: UseDef) -> Option<PathBuf> {
+    let (_, module) = item.path(db).split_last()?;
+    resolve_module_path(db, item.file(db), module)
+}
+
+/// The file named by the `module` segments of a `use` path in `file`.
+pub fn resolve_module_path(
+    db: &dyn crate::Db,
+    file: SchemaFile,
+    module: &[PathSegment],
+) -> Option<PathBuf> {
+    let workspace = db.workspace();
+    let alias = match module.first()? {
+        PathSegment::Ident(name, _) => workspace.aliases(db).get(name.text(db)),
+        PathSegment::String(..) => None,
+    };
+
+    let mut path = match (module.first()?, alias) {
+        // The alias replaces the first segment
+        (_, Some(alias)) => alias.clone(),
+        (PathSegment::Ident(..), None) => workspace.root(db).clone(),
+        (PathSegment::String(text, _), None) if is_url(text) => {
+            db.import_resolver().resolve_url(text)?
+        }
+        (PathSegment::String(..), None) => file.path(db).parent()?.to_owned(),
+    };
+
+    let skip = usize::from(alias.is_some());
+    for (index, segment) in module.iter().enumerate().skip(skip) {
+        match segment {
+            PathSegment::String(text, _) if is_url(text) => {
+                if index > 0 {
+                    return None;
+                }
+            }
+            PathSegment::Ident(name, _) if index + 1 == module.len() => {
+                path.push(format!("{}.{}", name.text(db), EXTENSION))
+            }
+            PathSegment::Ident(name, _) => path.push(name.text(db)),
+            PathSegment::String(text, _) => path.push(text),
+        }
+    }
+
+    // An alias can name the file itself
+    if alias.is_some() && module.len() == 1 && path.extension().is_none() {
+        path.set_extension(EXTENSION);
+    }
+
+    Some(normalize(&path))
+}
+
+/// The module part of a `use` path that imports from `target` in `file`,
+/// the inverse of [`module_path`]. Files below an alias or the root are
+/// named by identifiers, e.g. `a::b` for `a/b.kidl`, others by a quoted
+/// path relative to `file`.
+pub fn import_module(db: &dyn crate::Db, file: SchemaFile, target: SchemaFile) -> String {
+    let segments = import_segments(db, file, target);
+    match segments.as_slice() {
+        [path] if !is_identifier(path) => format!("\"{}\"", path),
+        _ => segments.join("::"),
+    }
+}
+
+/// The segments of [`import_module`] without quotes, e.g. to build the
+/// `use` item with `kidl_syn::ast::make::use_`.
+pub fn import_segments(db: &dyn crate::Db, file: SchemaFile, target: SchemaFile) -> Vec<String> {
+    let workspace = db.workspace();
+    let path = target.path(db);
+
+    for (alias, alias_path) in workspace.aliases(db) {
+        if let Some(segments) = module_segments(path, alias_path, Some(alias)) {
+            return segments;
+        }
+    }
+
+    if let Some(segments) = module_segments(path, workspace.root(db), None) {
+        if !workspace.aliases(db).contains_key(&segments[0]) {
+            return segments;
+        }
+    }
+
+    let dir = file.path(db).parent().unwrap_or(Path::new(""));
+    vec![relative_path(dir, path)]
+}
+
+/// `path` as identifier segments below `base`, after `first`.
+fn module_segments(path: &Path, base: &Path, first: Option<&String>) -> Option<Vec<String>> {
+    if path.extension()? != EXTENSION {
+        return None;
+    }
+
+    let base = match base.extension() {
+        Some(extension) if extension == EXTENSION => base.with_extension(""),
+        _ => base.to_owned(),
+    };
+    let relative = path.with_extension("");
+    let relative = relative.strip_prefix(base).ok()?;
+
+    let mut segments = first.into_iter().cloned().collect::<Vec<_>>();
+    for component in relative.components() {
+        let Component::Normal(text) = component else {
+            return None;
+        };
+
+        let text = text.to_str()?;
+        if !is_identifier(text) {
+            return None;
+        }
+        segments.push(text.to_owned());
+    }
+
+    match segments.is_empty() {
+        true => None,
+        false => Some(segments),
+    }
+}
+
+/// `path` relative to the directory `from`, starting with `./` or `../`.
+fn relative_path(from: &Path, path: &Path) -> String {
+    let from = from.components().collect::<Vec<_>>();
+    let path = path.components().collect::<Vec<_>>();
+    let common = from.iter().zip(&path).take_while(|(a, b)| a == b).count();
+
+    let mut segments = match common == from.len() {
+        true => vec![".".to_owned()],
+        false => vec!["..".to_owned(); from.len() - common],
+    };
+    segments.extend(
+        path[common..]
+            .iter()
+            .map(|component| component.as_os_str().to_string_lossy().into_owned()),
+    );
+    segments.join("/")
+}
+
+/// Span of the module part of a `use` path.
+fn module_span(db: &dyn crate::Db, item: UseDef) -> Span {
+    match item.path(db).as_slice() {
+        [first, .., last_module, _] => first.span().start..last_module.span().end,
+        [first, _] => first.span(),
+        _ => item.span(db),
+    }
+}
+
+/// The file `item` imports from, if it's loaded.
+#[salsa::tracked]
+pub fn resolve_import(db: &dyn crate::Db, item: UseDef) -> Option<SchemaFile> {
+    let file = item.file(db);
+    if item.path(db).len() < 2 {
+        Diagnostics::push(
+            db,
+            Diagnostic::error(
+                file,
+                item.span(db),
+                "Expected a module path, e.g. `use module::Item`",
+            ),
+        );
+        return None;
+    }
+
+    let Some(path) = module_path(db, item) else {
+        if let Some(url) = module_url(db, item) {
+            let message = format!("`{}` isn't vendored, run `kidl vendor`", url);
+            Diagnostics::push(db, Diagnostic::error(file, module_span(db, item), message));
+        }
+        return None;
+    };
+
+    let workspace = db.workspace();
+    let target = workspace.file(db, &path);
+    if target.is_none() {
+        let message = format!("File `{}` not found", workspace.display_path(db, &path));
+        Diagnostics::push(db, Diagnostic::error(file, module_span(db, item), message));
+    }
+
+    target
+}
+
+/// The item `item` imports.
+pub fn imported_item(db: &dyn crate::Db, item: UseDef) -> Option<Item> {
+    let name = item.imported(db)?;
+    let target = resolve_import(db, item)?;
+    lower_file(db, target)
+        .items(db)
+        .into_iter()
+        .find(|def| def.name(db) == name)
+}
+
+/// Files imported by `file`, without duplicates.
+#[salsa::tracked(return_ref)]
+pub fn file_imports(db: &dyn crate::Db, file: SchemaFile) -> Vec<SchemaFile> {
+    let mut imports = Vec::new();
+    for item in lower_file(db, file).uses(db) {
+        if let Some(target) = resolve_import(db, *item) {
+            if !imports.contains(&target) {
+                imports.push(target);
+            }
+        }
+    }
+    imports
+}
+
+/// Imports between all files of the workspace.
+#[derive(Debug, Clone, PartialEq, Eq, Default)]
+pub struct ImportGraph {
+    imports: HashMap<SchemaFile, Vec<SchemaFile>>,
+}
+
+impl ImportGraph {
+    pub fn imports(&self, file: SchemaFile) -> &[SchemaFile] {
+        self.imports
+            .get(&file)
+            .map_or(&[], |imports| imports.as_slice())
+    }
+
+    /// Files that import `file`.
+    pub fn importers(&self, file: SchemaFile) -> impl Iterator<Item = SchemaFile> + '_ {
+        self.imports
+            .iter()
+            .filter(move |(_, imports)| imports.contains(&file))
+            .map(|(importer, _)| *importer)
+    }
+}
+
+#[salsa::tracked(return_ref)]
+pub fn import_graph(db: &dyn crate::Db, workspace: Workspace) -> ImportGraph {
+    let imports = workspace
+        .files(db)
+        .values()
+        .map(|file| (*file, file_imports(db, *file).clone()))
+        .collect();
+
+    ImportGraph { imports }
+}
+
+/// Shortest chain of imports that leads from `from` to `to`, both included.
+fn import_chain(db: &dyn crate::Db, from: SchemaFile, to: SchemaFile) -> Option<Vec<SchemaFile>> {
+    let mut parents = HashMap::new();
+    let mut queue = VecDeque::from([from]);
+    parents.insert(from, from);
+
+    while let Some(file) = queue.pop_front() {
+        if file == to {
+            let mut chain = vec![to];
+            let mut current = to;
+            while current != from {
+                current = parents[&current];
+                chain.push(current);
+            }
+            chain.reverse();
+            return Some(chain);
+        }
+
+        for next in file_imports(db, file) {
+            if !parents.contains_key(next) {
+                parents.insert(*next, file);
+                queue.push_back(*next);
+            }
+        }
+    }
+
+    None
+}
+
+/// Reports a `use` of `name` that `target` doesn't declare. Imported
+/// names are not exported, the import has to name the declaring file.
+fn missing_item(
+    db: &dyn crate::Db,
+    file: SchemaFile,
+    target: SchemaFile,
+    name: Name,
+    span: Span,
+) -> Diagnostic {
+    let workspace = db.workspace();
+    let target_path = workspace.display_path(db, target.path(db));
+
+    let Some(Resolution::Import(reexport)) = file_scope(db, target).get(name) else {
+        let message = format!("`{}` isn't declared in `{}`", name.text(db), target_path);
+        return Diagnostic::error(file, span, message);
+    };
+
+    let message = match imported_item(db, reexport) {
+        Some(declaration) => format!(
+            "`{}` is imported by `{}`, not exported, import it from `{}`",
+            name.text(db),
+            target_path,
+            workspace.display_path(db, declaration.file(db).path(db))
+        ),
+        None => format!(
+            "`{}` is imported by `{}`, not exported",
+            name.text(db),
+            target_path
+        ),
+    };
+
+    Diagnostic::error(file, span, message).with_code(Code::NotExported)
+}
+
+/// Reports imports that can't be resolved and imports that lead back to
+/// `file`.
+#[salsa::tracked]
+pub fn check_imports(db: &dyn crate::Db, file: SchemaFile) {
+    let workspace = db.workspace();
+    for item in lower_file(db, file).uses(db) {
+        let Some(target) = resolve_import(db, *item) else {
+            continue;
+        };
+
+        if let Some(name) = item.imported(db) {
+            if imported_item(db, *item).is_none() {
+                let span = item.path(db).last().unwrap().span();
+                Diagnostics::push(db, missing_item(db, file, target, name, span));
+            }
+        }
+
+        if let Some(chain) = import_chain(db, target, file) {
+            let chain = std::iter::once(file)
+                .chain(chain)
+                .map(|f| workspace.display_path(db, f.path(db)))
+                .collect::<Vec<_>>();
+
+            let message = format!("Import cycle: {}", chain.join(" -> "));
+            Diagnostics::push(db, Diagnostic::error(file, item.span(db), message));
+        }
+    }
+}
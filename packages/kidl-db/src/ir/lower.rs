@@ -0,0 +1,117 @@
+use kidl_syn::{
+    ast::{
+        helpers::{trimmed_range, HasName},
+        path::PathSegment as AstPathSegment,
+        r#struct::{Struct, StructField},
+        r#type::Type,
+        r#use::Use,
+        schema::{Declaration, Schema},
+        AstNode, SyntaxNode, SyntaxToken,
+    },
+    helpers::ByteOffset,
+};
+use rowan::TextRange;
+
+use super::{FieldDef, Module, Name, PathSegment, SchemaFile, Span, StructDef, TypeRef, UseDef};
+
+/// Lowers the syntax tree of `file` into IR. Items with syntax errors are
+/// lowered as far as possible, items without a name are skipped. WebIDL
+/// files are lowered by [`super::webidl`].
+#[salsa::tracked]
+pub fn lower_file(db: &dyn crate::Db, file: SchemaFile) -> Module {
+    if super::webidl::is_webidl(file.path(db)) {
+        return super::webidl::lower_webidl(db, file);
+    }
+
+    let root = SyntaxNode::new_root(crate::source::parse(db, file));
+    let schema = Schema::cast(root).unwrap();
+
+    let uses = schema
+        .uses()
+        .map(|item| lower_use(db, file, &item))
+        .collect();
+
+    let structs = schema
+        .declarations()
+        .filter_map(|declaration| match declaration {
+            Declaration::Struct(item) => lower_struct(db, file, &item),
+        })
+        .collect();
+
+    Module::new(db, file, uses, structs, Vec::new(), Vec::new())
+}
+
+fn lower_use(db: &dyn crate::Db, file: SchemaFile, item: &Use) -> UseDef {
+    let path = item
+        .path()
+        .into_iter()
+        .flat_map(|path| path.segments())
+        .map(|segment| {
+            let span = token_span(segment.syntax());
+            match &segment {
+                AstPathSegment::Ident(ident) => {
+                    PathSegment::Ident(Name::new(db, ident.text().to_owned()), span)
+                }
+                AstPathSegment::String(_) => PathSegment::String(segment.text().to_owned(), span),
+            }
+        })
+        .collect();
+
+    UseDef::new(db, file, span(item.syntax()), path)
+}
+
+fn lower_struct(db: &dyn crate::Db, file: SchemaFile, item: &Struct) -> Option<StructDef> {
+    let name = item.name()?;
+    let fields = item
+        .fields()
+        .filter_map(|field| lower_field(db, &field))
+        .collect();
+
+    Some(StructDef::new(
+        db,
+        Name::new(db, name.text().to_owned()),
+        file,
+        span(item.syntax()),
+        token_span(&name.0),
+        fields,
+    ))
+}
+
+fn lower_field(db: &dyn crate::Db, field: &StructField) -> Option<FieldDef> {
+    let name = field.name()?;
+    let ty = field.ty().and_then(|ty| lower_type(db, &ty));
+
+    Some(FieldDef::new(
+        db,
+        Name::new(db, name.text().to_owned()),
+        span(field.syntax()),
+        token_span(&name.0),
+        field.is_optional().is_some(),
+        ty,
+    ))
+}
+
+fn lower_type(db: &dyn crate::Db, ty: &Type) -> Option<TypeRef> {
+    let name = ty.name()?;
+    Some(TypeRef {
+        name: Name::new(db, name.text().to_owned()),
+        args: ty
+            .arguments()
+            .filter_map(|arg| lower_type(db, &arg))
+            .collect(),
+        span: span(ty.syntax()),
+    })
+}
+
+fn text_span(range: TextRange) -> Span {
+    ByteOffset(range.start().into())..ByteOffset(range.end().into())
+}
+
+fn token_span(token: &SyntaxToken) -> Span {
+    text_span(token.text_range())
+}
+
+/// Range of `node` without the leading and trailing trivia.
+fn span(node: &SyntaxNode) -> Span {
+    text_span(trimmed_range(node))
+}
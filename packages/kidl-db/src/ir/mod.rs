@@ -1,4 +1,17 @@
-use std::path::PathBuf;
+//! Semantic model of schema files.
+//!
+//! The IR is produced from the syntax tree by [`lower_file`]. Everything
+//! past parsing (name resolution, checks, LSP features, generators) should
+//! work with these entities instead of rowan nodes.
+
+use std::{ops::Range, path::PathBuf};
+
+use kidl_syn::helpers::ByteOffset;
+
+mod lower;
+mod webidl;
+
+pub use lower::lower_file;
 
 #[salsa::input]
 pub struct SchemaFile {
@@ -6,3 +19,208 @@ pub struct SchemaFile {
     pub path: PathBuf,
     pub text: ropey::Rope,
 }
+
+/// Byte range of an entity in its file.
+pub type Span = Range<ByteOffset>;
+
+#[salsa::interned]
+pub struct Name {
+    #[return_ref]
+    pub text: String,
+}
+
+/// Items declared in a single file.
+#[salsa::tracked]
+pub struct Module {
+    pub file: SchemaFile,
+    #[return_ref]
+    pub uses: Vec<UseDef>,
+    #[return_ref]
+    pub structs: Vec<StructDef>,
+    #[return_ref]
+    pub enums: Vec<EnumDef>,
+    #[return_ref]
+    pub aliases: Vec<AliasDef>,
+}
+
+impl Module {
+    /// All declarations of the module, structs first.
+    pub fn items(self, db: &dyn crate::Db) -> Vec<Item> {
+        let structs = self.structs(db).iter().map(|d| Item::Struct(*d));
+        let enums = self.enums(db).iter().map(|d| Item::Enum(*d));
+        let aliases = self.aliases(db).iter().map(|d| Item::Alias(*d));
+        structs.chain(enums).chain(aliases).collect()
+    }
+
+    /// Types of all fields and aliases, without their type arguments.
+    pub fn type_refs(self, db: &dyn crate::Db) -> Vec<&TypeRef> {
+        let fields = self
+            .structs(db)
+            .iter()
+            .flat_map(|def| def.fields(db))
+            .filter_map(|field| field.ty(db).as_ref());
+        let aliases = self
+            .aliases(db)
+            .iter()
+            .filter_map(|alias| alias.ty(db).as_ref());
+        fields.chain(aliases).collect()
+    }
+}
+
+/// A declaration that can be referred to by name.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub enum Item {
+    Struct(StructDef),
+    Enum(EnumDef),
+    Alias(AliasDef),
+}
+
+impl Item {
+    pub fn name(self, db: &dyn crate::Db) -> Name {
+        match self {
+            Item::Struct(def) => def.name(db),
+            Item::Enum(def) => def.name(db),
+            Item::Alias(def) => def.name(db),
+        }
+    }
+
+    pub fn file(self, db: &dyn crate::Db) -> SchemaFile {
+        match self {
+            Item::Struct(def) => def.file(db),
+            Item::Enum(def) => def.file(db),
+            Item::Alias(def) => def.file(db),
+        }
+    }
+
+    pub fn span(self, db: &dyn crate::Db) -> Span {
+        match self {
+            Item::Struct(def) => def.span(db),
+            Item::Enum(def) => def.span(db),
+            Item::Alias(def) => def.span(db),
+        }
+    }
+
+    pub fn name_span(self, db: &dyn crate::Db) -> Span {
+        match self {
+            Item::Struct(def) => def.name_span(db),
+            Item::Enum(def) => def.name_span(db),
+            Item::Alias(def) => def.name_span(db),
+        }
+    }
+}
+
+#[salsa::tracked]
+pub struct StructDef {
+    #[id]
+    pub name: Name,
+    pub file: SchemaFile,
+    pub span: Span,
+    pub name_span: Span,
+    #[return_ref]
+    pub fields: Vec<FieldDef>,
+}
+
+#[salsa::tracked]
+pub struct FieldDef {
+    #[id]
+    pub name: Name,
+    pub span: Span,
+    pub name_span: Span,
+    pub optional: bool,
+    /// Missing when the field has a syntax error.
+    #[return_ref]
+    pub ty: Option<TypeRef>,
+}
+
+/// An enumeration of string values, e.g. a WebIDL `enum`.
+#[salsa::tracked]
+pub struct EnumDef {
+    #[id]
+    pub name: Name,
+    pub file: SchemaFile,
+    pub span: Span,
+    pub name_span: Span,
+    #[return_ref]
+    pub variants: Vec<Variant>,
+}
+
+/// A value of an enumeration.
+#[derive(Debug, Clone, PartialEq, Eq, Hash)]
+pub struct Variant {
+    pub name: Name,
+    /// The value as written, with quotes.
+    pub span: Span,
+}
+
+/// Another name for a type, e.g. a WebIDL `typedef`.
+#[salsa::tracked]
+pub struct AliasDef {
+    #[id]
+    pub name: Name,
+    pub file: SchemaFile,
+    pub span: Span,
+    pub name_span: Span,
+    /// Missing when the type can't be expressed in KIDL.
+    #[return_ref]
+    pub ty: Option<TypeRef>,
+}
+
+/// A reference to a type by name, e.g. `Map<String, User>`.
+#[derive(Debug, Clone, PartialEq, Eq, Hash)]
+pub struct TypeRef {
+    pub name: Name,
+    pub args: Vec<TypeRef>,
+    pub span: Span,
+}
+
+impl TypeRef {
+    /// Span of the name without the type arguments.
+    pub fn name_span(&self, db: &dyn crate::Db) -> Span {
+        let start = self.span.start;
+        start..start + ByteOffset(self.name.text(db).len())
+    }
+
+    /// The type as written in a schema, e.g. `Map<String, User>`.
+    pub fn text(&self, db: &dyn crate::Db) -> String {
+        let name = self.name.text(db);
+        if self.args.is_empty() {
+            return name.clone();
+        }
+
+        let args = self.args.iter().map(|arg| arg.text(db)).collect::<Vec<_>>();
+        format!("{}<{}>", name, args.join(", "))
+    }
+}
+
+#[salsa::tracked]
+pub struct UseDef {
+    pub file: SchemaFile,
+    pub span: Span,
+    #[return_ref]
+    pub path: Vec<PathSegment>,
+}
+
+#[derive(Debug, Clone, PartialEq, Eq, Hash)]
+pub enum PathSegment {
+    Ident(Name, Span),
+    /// A quoted segment, e.g. a file path or an URL, without quotes.
+    String(String, Span),
+}
+
+impl PathSegment {
+    pub fn span(&self) -> Span {
+        match self {
+            PathSegment::Ident(_, span) | PathSegment::String(_, span) => span.clone(),
+        }
+    }
+}
+
+impl UseDef {
+    /// The imported item, i.e. the last segment of the path.
+    pub fn imported(self, db: &dyn crate::Db) -> Option<Name> {
+        match self.path(db).last()? {
+            PathSegment::Ident(name, _) => Some(*name),
+            PathSegment::String(..) => None,
+        }
+    }
+}
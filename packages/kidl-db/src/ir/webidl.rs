@@ -0,0 +1,233 @@
+//! Lowering of WebIDL files, so that schemas can import their
+//! declarations: `use "./dom.webidl"::AbortSignal`.
+//!
+//! Interfaces, interface mixins and dictionaries become structs, with
+//! attributes and dictionary members as fields. Partial definitions are
+//! merged into the main one. Inherited members are not copied. Types that
+//! have no KIDL counterpart, like unions, `any` or callbacks, leave the
+//! field without a type. So do names the file doesn't declare.
+
+use std::{collections::HashSet, path::Path};
+
+use kidl_syn::helpers::ByteOffset;
+use kidl_webidl::ast::{Definition, Ident, Type, TypeKind};
+
+use crate::diagnostics::{Diagnostic, Diagnostics};
+
+use super::{
+    AliasDef, EnumDef, FieldDef, Module, Name, SchemaFile, Span, StructDef, TypeRef, Variant,
+};
+
+pub const EXTENSION: &str = "webidl";
+
+pub fn is_webidl(path: &Path) -> bool {
+    path.extension().is_some_and(|e| e == EXTENSION)
+}
+
+fn span(span: &kidl_webidl::Span) -> Span {
+    ByteOffset(span.start)..ByteOffset(span.end)
+}
+
+fn name(db: &dyn crate::Db, ident: &Ident) -> Name {
+    Name::new(db, ident.text.clone())
+}
+
+/// A struct being assembled from a definition and its partials.
+struct PendingStruct {
+    name: Name,
+    span: Span,
+    name_span: Span,
+    partial: bool,
+    fields: Vec<FieldDef>,
+}
+
+pub(super) fn lower_webidl(db: &dyn crate::Db, file: SchemaFile) -> Module {
+    let text = file.text(db).to_string();
+    let parsed = kidl_webidl::parse(&text);
+
+    for error in &parsed.errors {
+        Diagnostics::push(
+            db,
+            Diagnostic::error(file, span(&error.span), error.to_string()),
+        );
+    }
+
+    let declared = parsed
+        .definitions
+        .iter()
+        .map(|definition| definition.name().text.as_str())
+        .collect::<HashSet<_>>();
+
+    let mut structs: Vec<PendingStruct> = Vec::new();
+    let mut enums = Vec::new();
+    let mut aliases = Vec::new();
+
+    for definition in &parsed.definitions {
+        let (fields, partial, definition_span) = match definition {
+            Definition::Interface(interface) => {
+                let fields = interface
+                    .attributes
+                    .iter()
+                    .map(|a| field(db, &declared, &a.name, &a.ty, a.ty.nullable, &a.span))
+                    .collect::<Vec<_>>();
+                (fields, interface.partial, &interface.span)
+            }
+            Definition::Dictionary(dictionary) => {
+                let fields = dictionary
+                    .members
+                    .iter()
+                    .map(|m| field(db, &declared, &m.name, &m.ty, !m.required, &m.span))
+                    .collect::<Vec<_>>();
+                (fields, dictionary.partial, &dictionary.span)
+            }
+            Definition::Enum(definition) => {
+                let variants = definition
+                    .values
+                    .iter()
+                    .map(|v| Variant {
+                        name: Name::new(db, v.text.clone()),
+                        span: span(&v.span),
+                    })
+                    .collect();
+
+                enums.push(EnumDef::new(
+                    db,
+                    name(db, &definition.name),
+                    file,
+                    span(&definition.span),
+                    span(&definition.name.span),
+                    variants,
+                ));
+                continue;
+            }
+            Definition::Typedef(typedef) => {
+                aliases.push(AliasDef::new(
+                    db,
+                    name(db, &typedef.name),
+                    file,
+                    span(&typedef.span),
+                    span(&typedef.name.span),
+                    lower_type(db, &declared, &typedef.ty, true),
+                ));
+                continue;
+            }
+        };
+
+        let ident = definition.name();
+        let struct_name = name(db, ident);
+        match structs.iter_mut().find(|s| s.name == struct_name) {
+            Some(existing) => {
+                // The main definition provides the location
+                if existing.partial && !partial {
+                    existing.span = span(definition_span);
+                    existing.name_span = span(&ident.span);
+                    existing.partial = false;
+                }
+                existing.fields.extend(fields);
+            }
+            None => structs.push(PendingStruct {
+                name: struct_name,
+                span: span(definition_span),
+                name_span: span(&ident.span),
+                partial,
+                fields,
+            }),
+        }
+    }
+
+    let structs = structs
+        .into_iter()
+        .map(|s| StructDef::new(db, s.name, file, s.span, s.name_span, s.fields))
+        .collect();
+
+    Module::new(db, file, Vec::new(), structs, enums, aliases)
+}
+
+fn field(
+    db: &dyn crate::Db,
+    declared: &HashSet<&str>,
+    ident: &Ident,
+    ty: &Type,
+    optional: bool,
+    member_span: &kidl_webidl::Span,
+) -> FieldDef {
+    FieldDef::new(
+        db,
+        name(db, ident),
+        span(member_span),
+        span(&ident.span),
+        optional,
+        // A nullable field is optional, its type is not wrapped
+        lower_type(db, declared, ty, false),
+    )
+}
+
+/// KIDL name of a WebIDL type without type arguments.
+fn builtin_name(name: &str) -> Option<&'static str> {
+    Some(match name {
+        "boolean" => "bool",
+        "byte" => "i8",
+        "octet" => "u8",
+        "short" => "i16",
+        "unsigned short" => "u16",
+        "long" => "i32",
+        "unsigned long" => "u32",
+        "long long" => "i64",
+        "unsigned long long" => "u64",
+        "float" | "unrestricted float" => "f32",
+        "double" | "unrestricted double" => "f64",
+        "DOMString" | "USVString" | "ByteString" | "CSSOMString" => "string",
+        "ArrayBuffer" | "DataView" | "Uint8Array" => "bytes",
+        _ => return None,
+    })
+}
+
+/// Lowers `ty`, wrapping nullable types into `Option` if `wrap_nullable`
+/// is set. Nested nullable types are always wrapped. Names other than
+/// builtins have to be in `declared`.
+fn lower_type(
+    db: &dyn crate::Db,
+    declared: &HashSet<&str>,
+    ty: &Type,
+    wrap_nullable: bool,
+) -> Option<TypeRef> {
+    let TypeKind::Named { name, args } = &ty.kind else {
+        return None;
+    };
+
+    let (name, args) = match (name.as_str(), args.as_slice()) {
+        ("sequence" | "FrozenArray" | "ObservableArray", [item]) => {
+            ("List", vec![lower_type(db, declared, item, true)?])
+        }
+        ("record", [key, value]) => (
+            "Map",
+            vec![
+                lower_type(db, declared, key, true)?,
+                lower_type(db, declared, value, true)?,
+            ],
+        ),
+        ("Promise", [value]) => return lower_type(db, declared, value, wrap_nullable),
+        (name, []) => match builtin_name(name) {
+            Some(builtin) => (builtin, Vec::new()),
+            None if declared.contains(name) => (name, Vec::new()),
+            None => return None,
+        },
+        _ => return None,
+    };
+
+    let lowered = TypeRef {
+        name: Name::new(db, name.to_owned()),
+        args,
+        span: span(&ty.span),
+    };
+
+    if ty.nullable && wrap_nullable {
+        return Some(TypeRef {
+            name: Name::new(db, "Option".to_owned()),
+            args: vec![lowered],
+            span: span(&ty.span),
+        });
+    }
+
+    Some(lowered)
+}
@@ -4,13 +4,55 @@ pub use db::Database;
 mod cache;
 mod db;
 
+pub mod check;
+pub mod cycles;
+pub mod diagnostics;
+pub mod imports;
 pub mod ir;
+pub mod manifest;
+pub mod prelude;
+pub mod references;
+pub mod resolve;
 pub mod source;
+pub mod typeck;
+pub mod vendor;
+pub mod workspace;
 
 // ANCHOR: jar_struct
 #[salsa::jar(db = Db)]
-pub struct Jar(crate::ir::SchemaFile, crate::source::parse);
+pub struct Jar(
+    crate::workspace::Workspace,
+    crate::ir::SchemaFile,
+    crate::ir::Name,
+    crate::ir::Module,
+    crate::ir::StructDef,
+    crate::ir::FieldDef,
+    crate::ir::EnumDef,
+    crate::ir::AliasDef,
+    crate::ir::UseDef,
+    crate::ir::lower_file,
+    crate::source::parse,
+    crate::imports::resolve_import,
+    crate::imports::file_imports,
+    crate::imports::import_graph,
+    crate::imports::check_imports,
+    crate::resolve::file_scope,
+    crate::resolve::resolve_type_ref,
+    crate::resolve::check_names,
+    crate::resolve::check_unused_imports,
+    crate::typeck::check_types,
+    crate::references::file_references,
+    crate::references::reference_index,
+    crate::cycles::struct_edges,
+    crate::cycles::type_graph,
+    crate::cycles::file_type_graph,
+    crate::cycles::check_cycles,
+    crate::check::check_file,
+    crate::diagnostics::Diagnostics,
+);
 
 pub trait Db: salsa::DbWithJar<Jar> {
     fn cache(&self) -> Cache;
+    fn workspace(&self) -> workspace::Workspace;
+    fn import_resolver(&self) -> &dyn vendor::ImportResolver;
 }
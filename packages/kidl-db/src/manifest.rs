@@ -0,0 +1,285 @@
+//! The `kidl.toml` project manifest.
+//!
+//! ```toml
+//! [workspace]
+//! roots = ["schemas"]
+//! include = ["**/*.kidl"]
+//! exclude = ["schemas/legacy/**"]
+//!
+//! [imports]
+//! dom = "third_party/dom.webidl"
+//!
+//! [syntax]
+//! field_separators = ["comma", "newline"]
+//!
+//! [[target]]
+//! generator = "rust"
+//! out = "src/generated"
+//! ```
+//!
+//! Schema files are looked up in `roots`, and kept if their path relative
+//! to the project root matches `include` but not `exclude`. Hidden
+//! directories, like the vendor directory, are never searched. An import
+//! alias replaces the first segment of a module path: with the manifest
+//! above, `use dom::Event` imports `Event` from `third_party/dom.webidl`.
+//! The `syntax` section picks the tokens accepted between struct fields,
+//! at least one, only commas by default. A project without a manifest uses the defaults
+//! for all sections.
+
+use std::{
+    collections::BTreeMap,
+    fmt, fs, io,
+    path::{Path, PathBuf},
+};
+
+use globset::{Glob, GlobSet, GlobSetBuilder};
+use kidl_syn::parser::{ListSeparators, ParseOptions};
+use serde::{de::Error as _, Deserialize, Deserializer};
+
+use crate::workspace::normalize;
+
+pub const MANIFEST: &str = "kidl.toml";
+
+#[derive(Debug, Clone, Default, PartialEq, Deserialize)]
+#[serde(deny_unknown_fields)]
+pub struct Manifest {
+    #[serde(default)]
+    pub workspace: WorkspaceConfig,
+    /// Paths relative to the project root, by the module path segment
+    /// they replace.
+    #[serde(default)]
+    pub imports: BTreeMap<String, PathBuf>,
+    #[serde(default)]
+    pub syntax: SyntaxConfig,
+    #[serde(default, rename = "target")]
+    pub targets: Vec<Target>,
+}
+
+#[derive(Debug, Clone, PartialEq, Deserialize)]
+#[serde(deny_unknown_fields)]
+pub struct WorkspaceConfig {
+    #[serde(default = "default_roots")]
+    pub roots: Vec<PathBuf>,
+    #[serde(default = "default_include")]
+    pub include: Vec<String>,
+    #[serde(default)]
+    pub exclude: Vec<String>,
+}
+
+impl Default for WorkspaceConfig {
+    fn default() -> Self {
+        WorkspaceConfig {
+            roots: default_roots(),
+            include: default_include(),
+            exclude: Vec::new(),
+        }
+    }
+}
+
+fn default_roots() -> Vec<PathBuf> {
+    vec![PathBuf::from(".")]
+}
+
+fn default_include() -> Vec<String> {
+    vec![format!("**/*.{}", crate::imports::EXTENSION)]
+}
+
+#[derive(Debug, Clone, PartialEq, Deserialize)]
+#[serde(deny_unknown_fields)]
+pub struct SyntaxConfig {
+    #[serde(
+        default = "default_field_separators",
+        deserialize_with = "non_empty_separators"
+    )]
+    pub field_separators: Vec<Separator>,
+}
+
+impl Default for SyntaxConfig {
+    fn default() -> Self {
+        SyntaxConfig {
+            field_separators: default_field_separators(),
+        }
+    }
+}
+
+fn default_field_separators() -> Vec<Separator> {
+    vec![Separator::Comma]
+}
+
+/// Without any separator no struct with several fields would parse.
+fn non_empty_separators<'de, D: Deserializer<'de>>(
+    deserializer: D,
+) -> Result<Vec<Separator>, D::Error> {
+    let separators = Vec::<Separator>::deserialize(deserializer)?;
+    if separators.is_empty() {
+        return Err(D::Error::invalid_length(0, &"at least one separator"));
+    }
+    Ok(separators)
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Deserialize)]
+#[serde(rename_all = "lowercase")]
+pub enum Separator {
+    Comma,
+    Semicolon,
+    /// A line break alone.
+    Newline,
+}
+
+impl SyntaxConfig {
+    /// How the schema files of the project are parsed.
+    pub fn parse_options(&self) -> ParseOptions {
+        let has = |separator| self.field_separators.contains(&separator);
+        ParseOptions {
+            field_separators: ListSeparators {
+                comma: has(Separator::Comma),
+                semicolon: has(Separator::Semicolon),
+                newline: has(Separator::Newline),
+            },
+        }
+    }
+}
+
+/// Code generated from the schemas of the project.
+#[derive(Debug, Clone, PartialEq, Deserialize)]
+pub struct Target {
+    pub generator: String,
+    /// Output directory, relative to the project root.
+    pub out: PathBuf,
+    /// Settings of the generator.
+    #[serde(flatten)]
+    pub options: BTreeMap<String, toml::Value>,
+}
+
+/// An I/O or parse error, with the file it happened in.
+#[derive(Debug)]
+pub struct LoadError {
+    pub path: PathBuf,
+    pub error: io::Error,
+}
+
+impl LoadError {
+    fn new(path: &Path, error: impl Into<io::Error>) -> Self {
+        LoadError {
+            path: path.to_owned(),
+            error: error.into(),
+        }
+    }
+
+    fn invalid(path: &Path, error: impl std::error::Error + Send + Sync + 'static) -> Self {
+        LoadError::new(path, io::Error::new(io::ErrorKind::InvalidData, error))
+    }
+}
+
+impl fmt::Display for LoadError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "{}: {}", self.path.display(), self.error)
+    }
+}
+
+impl std::error::Error for LoadError {}
+
+impl Manifest {
+    pub fn load(path: &Path) -> Result<Manifest, LoadError> {
+        let text = fs::read_to_string(path).map_err(|e| LoadError::new(path, e))?;
+        toml::from_str(&text).map_err(|e| LoadError::invalid(path, e))
+    }
+
+    /// The manifest in `dir` or the closest of its ancestors.
+    pub fn find(dir: &Path) -> Option<PathBuf> {
+        dir.ancestors()
+            .map(|dir| dir.join(MANIFEST))
+            .find(|path| path.is_file())
+    }
+}
+
+/// A project directory and its manifest.
+#[derive(Debug, Clone, PartialEq)]
+pub struct Project {
+    pub root: PathBuf,
+    pub manifest: Manifest,
+}
+
+impl Project {
+    /// The project `dir` belongs to: the directory of the closest
+    /// manifest, or `dir` itself with the default manifest.
+    pub fn discover(dir: &Path) -> Result<Project, LoadError> {
+        match Manifest::find(dir) {
+            Some(path) => Ok(Project {
+                manifest: Manifest::load(&path)?,
+                root: path.parent().unwrap().to_owned(),
+            }),
+            None => Ok(Project {
+                root: dir.to_owned(),
+                manifest: Manifest::default(),
+            }),
+        }
+    }
+
+    /// Import aliases with absolute paths.
+    pub fn aliases(&self) -> BTreeMap<String, PathBuf> {
+        self.manifest
+            .imports
+            .iter()
+            .map(|(name, path)| (name.clone(), self.root.join(path)))
+            .collect()
+    }
+
+    /// All schema files of the project, sorted, and the entries that
+    /// couldn't be read. Those are skipped, only globs that don't parse fail
+    /// the whole listing.
+    pub fn files(&self) -> Result<(Vec<PathBuf>, Vec<LoadError>), LoadError> {
+        let config = &self.manifest.workspace;
+        let manifest_path = self.root.join(MANIFEST);
+        let include =
+            glob_set(&config.include).map_err(|e| LoadError::invalid(&manifest_path, e))?;
+        let exclude =
+            glob_set(&config.exclude).map_err(|e| LoadError::invalid(&manifest_path, e))?;
+
+        let root = normalize(&self.root);
+        let mut files = Vec::new();
+        let mut errors = Vec::new();
+        for dir in &config.roots {
+            let walker = walkdir::WalkDir::new(root.join(dir))
+                .into_iter()
+                .filter_entry(|entry| entry.depth() == 0 || !is_hidden(entry));
+
+            for entry in walker {
+                let entry = match entry {
+                    Ok(entry) => entry,
+                    Err(e) => {
+                        let path = e.path().unwrap_or(&root).to_owned();
+                        errors.push(LoadError::new(&path, e));
+                        continue;
+                    }
+                };
+
+                if !entry.file_type().is_file() {
+                    continue;
+                }
+
+                let path = normalize(entry.path());
+                let relative = path.strip_prefix(&root).unwrap_or(&path);
+                if include.is_match(relative) && !exclude.is_match(relative) {
+                    files.push(path);
+                }
+            }
+        }
+
+        files.sort();
+        files.dedup();
+        Ok((files, errors))
+    }
+}
+
+fn is_hidden(entry: &walkdir::DirEntry) -> bool {
+    entry.file_name().to_string_lossy().starts_with('.')
+}
+
+fn glob_set(patterns: &[String]) -> Result<GlobSet, globset::Error> {
+    let mut builder = GlobSetBuilder::new();
+    for pattern in patterns {
+        builder.add(Glob::new(pattern)?);
+    }
+    builder.build()
+}
@@ -0,0 +1,102 @@
+//! Built-in types. They are in scope in every file without an import.
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, PartialOrd, Ord, Hash)]
+pub enum Builtin {
+    I8,
+    I16,
+    I32,
+    I64,
+    U8,
+    U16,
+    U32,
+    U64,
+    F32,
+    F64,
+    Bool,
+    String,
+    Bytes,
+    Timestamp,
+    Duration,
+    Uuid,
+    List,
+    Map,
+    Set,
+    Option,
+}
+
+impl Builtin {
+    pub const ALL: [Builtin; 20] = [
+        Builtin::I8,
+        Builtin::I16,
+        Builtin::I32,
+        Builtin::I64,
+        Builtin::U8,
+        Builtin::U16,
+        Builtin::U32,
+        Builtin::U64,
+        Builtin::F32,
+        Builtin::F64,
+        Builtin::Bool,
+        Builtin::String,
+        Builtin::Bytes,
+        Builtin::Timestamp,
+        Builtin::Duration,
+        Builtin::Uuid,
+        Builtin::List,
+        Builtin::Map,
+        Builtin::Set,
+        Builtin::Option,
+    ];
+
+    pub fn name(self) -> &'static str {
+        match self {
+            Builtin::I8 => "i8",
+            Builtin::I16 => "i16",
+            Builtin::I32 => "i32",
+            Builtin::I64 => "i64",
+            Builtin::U8 => "u8",
+            Builtin::U16 => "u16",
+            Builtin::U32 => "u32",
+            Builtin::U64 => "u64",
+            Builtin::F32 => "f32",
+            Builtin::F64 => "f64",
+            Builtin::Bool => "bool",
+            Builtin::String => "string",
+            Builtin::Bytes => "bytes",
+            Builtin::Timestamp => "timestamp",
+            Builtin::Duration => "duration",
+            Builtin::Uuid => "uuid",
+            Builtin::List => "List",
+            Builtin::Map => "Map",
+            Builtin::Set => "Set",
+            Builtin::Option => "Option",
+        }
+    }
+
+    pub fn from_name(name: &str) -> Option<Builtin> {
+        Builtin::ALL.into_iter().find(|b| b.name() == name)
+    }
+
+    /// Number of type arguments the type takes.
+    pub fn arity(self) -> usize {
+        match self {
+            Builtin::List | Builtin::Set | Builtin::Option => 1,
+            Builtin::Map => 2,
+            _ => 0,
+        }
+    }
+
+    /// Whether values of the type can be used as map keys.
+    pub fn is_hashable(self) -> bool {
+        match self {
+            Builtin::F32 | Builtin::F64 => false,
+            _ => self.arity() == 0,
+        }
+    }
+}
+
+impl std::fmt::Display for Builtin {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        f.write_str(self.name())
+    }
+}
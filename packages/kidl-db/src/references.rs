@@ -0,0 +1,127 @@
+//! Where items are referred to by name.
+//!
+//! Each file records the references it contains, [`reference_index`]
+//! gathers them for the whole workspace. A reference to an imported name
+//! counts as a reference to the declaration it imports, so renaming or
+//! searching a struct also finds its uses in the importing files.
+
+use std::collections::HashMap;
+
+use crate::{
+    imports::imported_item,
+    ir::{lower_file, Item, SchemaFile, Span, TypeRef},
+    resolve::{resolve_type_ref, Resolution},
+    workspace::Workspace,
+};
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, PartialOrd, Ord, Hash)]
+pub enum ReferenceKind {
+    /// The type of a struct field.
+    FieldType,
+    /// The type an alias stands for.
+    AliasType,
+    /// An argument of a generic type, e.g. `B` in `List<B>`.
+    TypeArgument,
+    /// The last segment of a `use` path.
+    Import,
+}
+
+#[derive(Debug, Clone, PartialEq, Eq, Hash)]
+pub struct Reference {
+    pub file: SchemaFile,
+    /// The name itself, without type arguments.
+    pub span: Span,
+    pub kind: ReferenceKind,
+}
+
+/// References in `file`, with the item they refer to.
+#[salsa::tracked(return_ref)]
+pub fn file_references(db: &dyn crate::Db, file: SchemaFile) -> Vec<(Item, Reference)> {
+    let module = lower_file(db, file);
+    let mut references = Vec::new();
+
+    for def in module.structs(db) {
+        for field in def.fields(db) {
+            if let Some(ty) = field.ty(db) {
+                collect(db, file, ty, ReferenceKind::FieldType, &mut references);
+            }
+        }
+    }
+
+    for alias in module.aliases(db) {
+        if let Some(ty) = alias.ty(db) {
+            collect(db, file, ty, ReferenceKind::AliasType, &mut references);
+        }
+    }
+
+    for item in module.uses(db) {
+        let (Some(imported), Some(segment)) = (imported_item(db, *item), item.path(db).last())
+        else {
+            continue;
+        };
+
+        let reference = Reference {
+            file,
+            span: segment.span(),
+            kind: ReferenceKind::Import,
+        };
+        references.push((imported, reference));
+    }
+
+    references.sort_by_key(|(_, reference)| reference.span.start);
+    references
+}
+
+fn collect(
+    db: &dyn crate::Db,
+    file: SchemaFile,
+    ty: &TypeRef,
+    kind: ReferenceKind,
+    references: &mut Vec<(Item, Reference)>,
+) {
+    let item = match resolve_type_ref(db, file, ty.name) {
+        Some(Resolution::Local(item)) => Some(item),
+        Some(Resolution::Import(item)) => imported_item(db, item),
+        Some(Resolution::Builtin(_)) | None => None,
+    };
+
+    if let Some(item) = item {
+        let reference = Reference {
+            file,
+            span: ty.name_span(db),
+            kind,
+        };
+        references.push((item, reference));
+    }
+
+    for arg in &ty.args {
+        collect(db, file, arg, ReferenceKind::TypeArgument, references);
+    }
+}
+
+/// References to every item of the workspace.
+#[derive(Debug, Clone, PartialEq, Eq, Default)]
+pub struct ReferenceIndex {
+    references: HashMap<Item, Vec<Reference>>,
+}
+
+impl ReferenceIndex {
+    /// References to `item`, grouped by file and in source order.
+    pub fn references(&self, item: Item) -> &[Reference] {
+        self.references
+            .get(&item)
+            .map_or(&[], |references| references.as_slice())
+    }
+}
+
+#[salsa::tracked(return_ref)]
+pub fn reference_index(db: &dyn crate::Db, workspace: Workspace) -> ReferenceIndex {
+    let mut references: HashMap<Item, Vec<Reference>> = HashMap::new();
+    for file in workspace.files(db).values() {
+        for (item, reference) in file_references(db, *file) {
+            references.entry(*item).or_default().push(reference.clone());
+        }
+    }
+
+    ReferenceIndex { references }
+}
@@ -0,0 +1,204 @@
+//! Name resolution.
+//!
+//! Every file has its own scope made of the items it declares and the
+//! names it imports with `use`. Built-in types from [`crate::prelude`] are
+//! visible everywhere but can be shadowed by a local declaration.
+
+use std::collections::{HashMap, HashSet};
+
+use crate::{
+    diagnostics::{Code, Diagnostic, Diagnostics},
+    ir::{lower_file, Item, Name, SchemaFile, TypeRef, UseDef},
+    prelude::Builtin,
+};
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub enum Resolution {
+    /// An item declared in the same file.
+    Local(Item),
+    /// A name brought into scope by `use`.
+    Import(UseDef),
+    Builtin(Builtin),
+}
+
+/// Items visible in a file, by name. A name can have several entries when
+/// it's declared or imported more than once; the first one wins.
+#[derive(Debug, Clone, PartialEq, Eq, Default)]
+pub struct Scope {
+    items: HashMap<Name, Vec<Resolution>>,
+}
+
+impl Scope {
+    pub fn get(&self, name: Name) -> Option<Resolution> {
+        self.items
+            .get(&name)
+            .and_then(|items| items.first().copied())
+    }
+
+    pub fn iter(&self) -> impl Iterator<Item = (Name, Resolution)> + '_ {
+        self.items
+            .iter()
+            .filter_map(|(name, items)| Some((*name, *items.first()?)))
+    }
+
+    fn insert(&mut self, name: Name, resolution: Resolution) {
+        self.items.entry(name).or_default().push(resolution)
+    }
+}
+
+#[salsa::tracked(return_ref)]
+pub fn file_scope(db: &dyn crate::Db, file: SchemaFile) -> Scope {
+    let module = lower_file(db, file);
+    let mut scope = Scope::default();
+
+    let items = module
+        .items(db)
+        .into_iter()
+        .map(|item| (item.name(db), Resolution::Local(item)));
+    let imports = module
+        .uses(db)
+        .iter()
+        .filter_map(|item| Some((item.imported(db)?, Resolution::Import(*item))));
+
+    for (name, resolution) in items.chain(imports) {
+        if let Some(first) = scope.get(name) {
+            if let Some(diagnostic) = conflict(db, file, name, first, resolution) {
+                Diagnostics::push(db, diagnostic);
+            }
+        }
+
+        scope.insert(name, resolution);
+    }
+
+    scope
+}
+
+fn conflict(
+    db: &dyn crate::Db,
+    file: SchemaFile,
+    name: Name,
+    first: Resolution,
+    second: Resolution,
+) -> Option<Diagnostic> {
+    let text = name.text(db);
+    match (first, second) {
+        (Resolution::Import(first), Resolution::Import(second)) => {
+            if first.path(db) == second.path(db) {
+                return None;
+            }
+
+            Some(
+                Diagnostic::error(
+                    file,
+                    second.span(db),
+                    format!("Ambiguous import, `{}` is already imported", text),
+                )
+                .with_code(Code::AmbiguousImport),
+            )
+        }
+        (_, Resolution::Local(def)) => Some(
+            Diagnostic::error(
+                file,
+                def.name_span(db),
+                format!("Duplicate definition of `{}`", text),
+            )
+            .with_code(Code::DuplicateDefinition),
+        ),
+        (_, Resolution::Import(item)) => Some(
+            Diagnostic::error(
+                file,
+                item.span(db),
+                format!("Import of `{}` shadows its declaration in this file", text),
+            )
+            .with_code(Code::ImportShadowsDeclaration),
+        ),
+        (_, Resolution::Builtin(_)) => None,
+    }
+}
+
+/// Resolves a type name as it's written in `file`. Tracked, so that
+/// editor requests and checks share the result until the scope of `file`
+/// changes.
+#[salsa::tracked]
+pub fn resolve_type_ref(db: &dyn crate::Db, file: SchemaFile, name: Name) -> Option<Resolution> {
+    if let Some(resolution) = file_scope(db, file).get(name) {
+        return Some(resolution);
+    }
+
+    Builtin::from_name(name.text(db)).map(Resolution::Builtin)
+}
+
+/// Reports type references that don't resolve.
+#[salsa::tracked]
+pub fn check_names(db: &dyn crate::Db, file: SchemaFile) {
+    let module = lower_file(db, file);
+    for def in module.structs(db) {
+        for field in def.fields(db) {
+            if let Some(ty) = field.ty(db) {
+                check_type_ref(db, file, ty);
+            }
+        }
+    }
+
+    for alias in module.aliases(db) {
+        if let Some(ty) = alias.ty(db) {
+            check_type_ref(db, file, ty);
+        }
+    }
+}
+
+fn check_type_ref(db: &dyn crate::Db, file: SchemaFile, ty: &TypeRef) {
+    if resolve_type_ref(db, file, ty.name).is_none() {
+        Diagnostics::push(
+            db,
+            Diagnostic::error(
+                file,
+                ty.name_span(db),
+                format!("Unresolved type `{}`", ty.name.text(db)),
+            ),
+        );
+    }
+
+    for arg in &ty.args {
+        check_type_ref(db, file, arg);
+    }
+}
+
+/// Reports imports that no type reference of `file` resolves to. Imports
+/// that clash with a declaration are already reported by [`file_scope`].
+#[salsa::tracked]
+pub fn check_unused_imports(db: &dyn crate::Db, file: SchemaFile) {
+    let module = lower_file(db, file);
+    let mut used = HashSet::new();
+    for ty in module.type_refs(db) {
+        collect_imports(db, file, ty, &mut used);
+    }
+
+    for item in module.uses(db) {
+        let Some(name) = item.imported(db) else {
+            continue;
+        };
+
+        if used.contains(item)
+            || matches!(file_scope(db, file).get(name), Some(Resolution::Local(_)))
+        {
+            continue;
+        }
+
+        let message = format!("Unused import `{}`", name.text(db));
+        Diagnostics::push(
+            db,
+            Diagnostic::warning(file, item.span(db), message).with_code(Code::UnusedImport),
+        );
+    }
+}
+
+fn collect_imports(db: &dyn crate::Db, file: SchemaFile, ty: &TypeRef, used: &mut HashSet<UseDef>) {
+    if let Some(Resolution::Import(item)) = resolve_type_ref(db, file, ty.name) {
+        used.insert(item);
+    }
+
+    for arg in &ty.args {
+        collect_imports(db, file, arg, used);
+    }
+}
@@ -1,12 +1,25 @@
 use kidl_syn::{lexer::tokenize, source::RopeSource};
 use rowan::GreenNode;
 
-use crate::ir::SchemaFile;
+use crate::{
+    diagnostics::{Diagnostic, Diagnostics},
+    ir::SchemaFile,
+};
 
 #[salsa::tracked]
 pub fn parse(db: &dyn crate::Db, source: SchemaFile) -> GreenNode {
     let text = source.text(db);
     let cache = db.cache();
     let mut cache = cache.node();
-    kidl_syn::parser::parse(tokenize(RopeSource::new(text.slice(..))), &mut cache).schema
+    let parsed = kidl_syn::parser::parse_with_options(
+        tokenize(RopeSource::new(text.slice(..))),
+        &mut cache,
+        db.workspace().parse_options(db),
+    );
+
+    for error in &parsed.errors {
+        Diagnostics::push(db, Diagnostic::from_syntax(source, error));
+    }
+
+    parsed.schema
 }
@@ -0,0 +1,99 @@
+//! Checks of type references that go beyond resolving their names.
+
+use crate::{
+    cycles::MAX_ALIAS_DEPTH,
+    diagnostics::{Diagnostic, Diagnostics},
+    imports::imported_item,
+    ir::{lower_file, Item, SchemaFile, TypeRef},
+    prelude::Builtin,
+    resolve::{resolve_type_ref, Resolution},
+};
+
+/// Reports type arguments that don't match the generic parameters of the
+/// type, and map keys that can't be hashed.
+#[salsa::tracked]
+pub fn check_types(db: &dyn crate::Db, file: SchemaFile) {
+    let module = lower_file(db, file);
+    for def in module.structs(db) {
+        for field in def.fields(db) {
+            if let Some(ty) = field.ty(db) {
+                check_type_ref(db, file, ty);
+            }
+        }
+    }
+
+    for alias in module.aliases(db) {
+        if let Some(ty) = alias.ty(db) {
+            check_type_ref(db, file, ty);
+        }
+    }
+}
+
+fn check_type_ref(db: &dyn crate::Db, file: SchemaFile, ty: &TypeRef) {
+    for arg in &ty.args {
+        check_type_ref(db, file, arg);
+    }
+
+    let name = ty.name.text(db);
+    let resolution = resolve_type_ref(db, file, ty.name);
+    // Declarations are never generic
+    let arity = match resolution {
+        Some(Resolution::Builtin(builtin)) => builtin.arity(),
+        Some(Resolution::Local(_)) => 0,
+        Some(Resolution::Import(item)) if imported_item(db, item).is_some() => 0,
+        // Reported by name resolution and import checks
+        Some(Resolution::Import(_)) | None => return,
+    };
+
+    if arity == 0 && !ty.args.is_empty() {
+        let message = format!("Type `{}` doesn't take type arguments", name);
+        Diagnostics::push(db, Diagnostic::error(file, ty.span.clone(), message));
+        return;
+    }
+
+    if ty.args.len() != arity {
+        let message = format!(
+            "Type `{}` takes {} type argument{}, but {} {} given",
+            name,
+            arity,
+            if arity == 1 { "" } else { "s" },
+            ty.args.len(),
+            if ty.args.len() == 1 { "was" } else { "were" },
+        );
+        Diagnostics::push(db, Diagnostic::error(file, ty.span.clone(), message));
+        return;
+    }
+
+    if resolution == Some(Resolution::Builtin(Builtin::Map)) {
+        check_map_key(db, file, &ty.args[0]);
+    }
+}
+
+fn check_map_key(db: &dyn crate::Db, file: SchemaFile, key: &TypeRef) {
+    if is_hashable(db, file, key, 0) == Some(false) {
+        let message = format!(
+            "Type `{}` can't be used as a map key, expected a hashable primitive",
+            key.name.text(db)
+        );
+        Diagnostics::push(db, Diagnostic::error(file, key.span.clone(), message));
+    }
+}
+
+/// Whether values of `ty`, written in `file`, can be map keys, following
+/// aliases to the type they name. `None` when the type doesn't resolve.
+fn is_hashable(db: &dyn crate::Db, file: SchemaFile, ty: &TypeRef, depth: usize) -> Option<bool> {
+    let item = match resolve_type_ref(db, file, ty.name)? {
+        Resolution::Builtin(builtin) => return Some(builtin.is_hashable()),
+        Resolution::Local(item) => item,
+        Resolution::Import(item) => imported_item(db, item)?,
+    };
+
+    match item {
+        Item::Enum(_) => Some(true),
+        Item::Struct(_) => Some(false),
+        Item::Alias(alias) if depth < MAX_ALIAS_DEPTH => {
+            is_hashable(db, alias.file(db), alias.ty(db).as_ref()?, depth + 1)
+        }
+        Item::Alias(_) => None,
+    }
+}
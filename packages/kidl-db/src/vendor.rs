@@ -0,0 +1,213 @@
+//! Imports from URLs.
+//!
+//! Queries never touch the network. URL imports are mapped to local files
+//! by an [`ImportResolver`]; [`VendorCache`] keeps downloaded files in the
+//! project together with a lockfile of their checksums, so the same
+//! sources are used on every machine. `kidl vendor` fills the cache.
+
+use std::{
+    collections::HashMap,
+    fs, io,
+    path::{Path, PathBuf},
+};
+
+use serde::{Deserialize, Serialize};
+use sha2::{Digest, Sha256};
+
+pub const LOCKFILE: &str = "kidl.lock";
+pub const VENDOR_DIR: &str = ".kidl/vendor";
+
+/// Maps URL imports to local files.
+///
+/// Results are memoized by the database, so a resolver must not change its
+/// answers while the database is alive.
+pub trait ImportResolver: Send + Sync {
+    fn resolve_url(&self, url: &str) -> Option<PathBuf>;
+}
+
+/// Resolves nothing, URL imports are reported as not vendored.
+pub struct NoUrlImports;
+
+impl ImportResolver for NoUrlImports {
+    fn resolve_url(&self, _url: &str) -> Option<PathBuf> {
+        None
+    }
+}
+
+pub fn is_url(path: &str) -> bool {
+    path.contains("://")
+}
+
+pub fn checksum(contents: &[u8]) -> String {
+    format!("{:x}", Sha256::digest(contents))
+}
+
+#[derive(Debug, Clone, Default, PartialEq, Eq, Serialize, Deserialize)]
+pub struct Lockfile {
+    #[serde(default, rename = "import")]
+    pub imports: Vec<LockedImport>,
+}
+
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct LockedImport {
+    pub url: String,
+    /// File name in the vendor directory.
+    pub file: String,
+    pub sha256: String,
+}
+
+impl Lockfile {
+    /// Reads a lockfile, a missing file is an empty lockfile.
+    pub fn load(path: &Path) -> io::Result<Lockfile> {
+        match fs::read_to_string(path) {
+            Ok(text) => {
+                toml::from_str(&text).map_err(|e| io::Error::new(io::ErrorKind::InvalidData, e))
+            }
+            Err(e) if e.kind() == io::ErrorKind::NotFound => Ok(Lockfile::default()),
+            Err(e) => Err(e),
+        }
+    }
+
+    pub fn save(&self, path: &Path) -> io::Result<()> {
+        let text =
+            toml::to_string(self).map_err(|e| io::Error::new(io::ErrorKind::InvalidData, e))?;
+        fs::write(path, text)
+    }
+
+    pub fn get(&self, url: &str) -> Option<&LockedImport> {
+        self.imports.iter().find(|i| i.url == url)
+    }
+
+    /// Adds or replaces the entry for `import.url`, keeping entries sorted.
+    pub fn insert(&mut self, import: LockedImport) {
+        match self.imports.binary_search_by(|i| i.url.cmp(&import.url)) {
+            Ok(index) => self.imports[index] = import,
+            Err(index) => self.imports.insert(index, import),
+        }
+    }
+}
+
+/// A directory of downloaded imports listed in a lockfile.
+pub struct VendorCache {
+    dir: PathBuf,
+    lockfile_path: PathBuf,
+    lockfile: Lockfile,
+    /// Files whose contents match the lockfile, by URL.
+    verified: HashMap<String, PathBuf>,
+    problems: Vec<String>,
+}
+
+impl VendorCache {
+    /// Opens the cache of the project in `root` and verifies the
+    /// checksums of all vendored files.
+    pub fn open(root: &Path) -> io::Result<VendorCache> {
+        let lockfile_path = root.join(LOCKFILE);
+        let mut cache = VendorCache {
+            dir: root.join(VENDOR_DIR),
+            lockfile: Lockfile::load(&lockfile_path)?,
+            lockfile_path,
+            verified: HashMap::new(),
+            problems: Vec::new(),
+        };
+
+        cache.verify();
+        Ok(cache)
+    }
+
+    fn verify(&mut self) {
+        self.verified.clear();
+        self.problems.clear();
+
+        for import in &self.lockfile.imports {
+            let path = self.dir.join(&import.file);
+            match fs::read(&path) {
+                Ok(contents) if checksum(&contents) == import.sha256 => {
+                    self.verified.insert(import.url.clone(), path);
+                }
+                Ok(_) => self.problems.push(format!(
+                    "{}: checksum doesn't match the lockfile, run `kidl vendor` again",
+                    path.display()
+                )),
+                Err(e) => self
+                    .problems
+                    .push(format!("{}: {} ({})", path.display(), e, import.url)),
+            }
+        }
+    }
+
+    pub fn lockfile(&self) -> &Lockfile {
+        &self.lockfile
+    }
+
+    /// Vendored files that are missing or don't match their checksum.
+    pub fn problems(&self) -> &[String] {
+        &self.problems
+    }
+
+    pub fn is_vendored(&self, url: &str) -> bool {
+        self.verified.contains_key(url)
+    }
+
+    /// Name of the file that stores `url`: a hash of the URL, so that
+    /// any URL makes a valid file name, and its extension.
+    pub fn file_name(url: &str) -> String {
+        let hash = checksum(url.as_bytes());
+        let extension = url
+            .rsplit('/')
+            .next()
+            .and_then(|name| name.rsplit_once('.'))
+            .map(|(_, extension)| extension)
+            .filter(|extension| extension.chars().all(|c| c.is_ascii_alphanumeric()));
+
+        match extension {
+            Some(extension) => format!("{}.{}", &hash[..16], extension),
+            None => hash[..16].to_owned(),
+        }
+    }
+
+    /// Writes the downloaded `contents` of `url` and records them in the
+    /// lockfile. Contents that don't match an existing entry of the
+    /// lockfile are an error and nothing is written, only
+    /// [`VendorCache::update`] replaces entries. Call [`VendorCache::save`]
+    /// to write the lockfile.
+    pub fn store(&mut self, url: &str, contents: &[u8]) -> io::Result<PathBuf> {
+        if let Some(locked) = self.lockfile.get(url) {
+            if checksum(contents) != locked.sha256 {
+                return Err(io::Error::new(
+                    io::ErrorKind::InvalidData,
+                    "checksum doesn't match the lockfile, run `kidl vendor --update` to accept it",
+                ));
+            }
+        }
+
+        self.update(url, contents)
+    }
+
+    /// Like [`VendorCache::store`], but replaces the entry of `url` in the
+    /// lockfile whatever its checksum was.
+    pub fn update(&mut self, url: &str, contents: &[u8]) -> io::Result<PathBuf> {
+        fs::create_dir_all(&self.dir)?;
+
+        let file = Self::file_name(url);
+        let path = self.dir.join(&file);
+        fs::write(&path, contents)?;
+
+        self.lockfile.insert(LockedImport {
+            url: url.to_owned(),
+            file,
+            sha256: checksum(contents),
+        });
+        self.verified.insert(url.to_owned(), path.clone());
+        Ok(path)
+    }
+
+    pub fn save(&self) -> io::Result<()> {
+        self.lockfile.save(&self.lockfile_path)
+    }
+}
+
+impl ImportResolver for VendorCache {
+    fn resolve_url(&self, url: &str) -> Option<PathBuf> {
+        self.verified.get(url).cloned()
+    }
+}
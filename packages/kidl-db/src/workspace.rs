@@ -0,0 +1,56 @@
+//! The set of files that make up a project.
+
+use std::{
+    collections::BTreeMap,
+    path::{Component, Path, PathBuf},
+};
+
+use kidl_syn::parser::ParseOptions;
+
+use crate::ir::SchemaFile;
+
+/// All files known to the database. Module paths in `use` items are
+/// resolved against `root`, or against an alias from the manifest.
+#[salsa::input]
+pub struct Workspace {
+    #[return_ref]
+    pub root: PathBuf,
+    /// Absolute paths, by the first module path segment they replace.
+    #[return_ref]
+    pub aliases: BTreeMap<String, PathBuf>,
+    /// How all schema files are parsed, from the manifest.
+    pub parse_options: ParseOptions,
+    #[return_ref]
+    pub files: BTreeMap<PathBuf, SchemaFile>,
+}
+
+impl Workspace {
+    pub fn file(self, db: &dyn crate::Db, path: &Path) -> Option<SchemaFile> {
+        self.files(db).get(path).copied()
+    }
+
+    /// `path` relative to the root, for messages.
+    pub fn display_path(self, db: &dyn crate::Db, path: &Path) -> String {
+        path.strip_prefix(self.root(db))
+            .unwrap_or(path)
+            .display()
+            .to_string()
+    }
+}
+
+/// Removes `.` and resolves `..` components without touching the disk.
+pub fn normalize(path: &Path) -> PathBuf {
+    let mut normalized = PathBuf::new();
+    for component in path.components() {
+        match component {
+            Component::CurDir => {}
+            Component::ParentDir => {
+                if !normalized.pop() {
+                    normalized.push(component);
+                }
+            }
+            _ => normalized.push(component),
+        }
+    }
+    normalized
+}
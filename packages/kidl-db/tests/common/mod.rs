@@ -0,0 +1,65 @@
+//! Helpers shared by the database tests. Nothing here touches the
+//! network, and only [`temp_dir`] touches the disk.
+
+#![allow(dead_code)]
+
+use std::path::{Path, PathBuf};
+
+use kidl_db::{
+    diagnostics::file_diagnostics,
+    ir::{SchemaFile, Span},
+    Database,
+};
+
+pub const ROOT: &str = "/project";
+
+pub fn path(relative: &str) -> PathBuf {
+    Path::new(ROOT).join(relative)
+}
+
+/// A database for a project in [`ROOT`] with `files`, given as paths
+/// relative to the root and their text.
+pub fn database(files: &[(&str, &str)]) -> Database {
+    let mut db = Database::new(PathBuf::from(ROOT));
+    for (relative, text) in files {
+        db.push_file(path(relative), text.to_string());
+    }
+    db
+}
+
+pub fn file(db: &Database, relative: &str) -> SchemaFile {
+    db.schema_file(&path(relative))
+        .unwrap_or_else(|| panic!("{} isn't loaded", relative))
+}
+
+/// The text of `span` in `file`.
+pub fn text(db: &Database, file: SchemaFile, span: &Span) -> String {
+    file.text(db)
+        .byte_slice(span.start.0..span.end.0)
+        .to_string()
+}
+
+/// Messages of all diagnostics of the file at `relative`.
+pub fn messages(db: &Database, relative: &str) -> Vec<String> {
+    file_diagnostics(db, file(db, relative))
+        .into_iter()
+        .map(|diagnostic| diagnostic.message)
+        .collect()
+}
+
+/// An empty directory for the test `name` to write to.
+pub fn temp_dir(name: &str) -> PathBuf {
+    let dir = std::env::temp_dir().join(format!("kidl-db-{}-{}", name, std::process::id()));
+    let _ = std::fs::remove_dir_all(&dir);
+    std::fs::create_dir_all(&dir).unwrap();
+    dir
+}
+
+/// Writes `files`, given as paths relative to `dir` and their text.
+pub fn write_files(dir: &Path, files: &[(&str, &str)]) {
+    for (relative, text) in files {
+        let path = dir.join(relative);
+        std::fs::create_dir_all(path.parent().unwrap()).unwrap();
+        std::fs::write(path, text).unwrap();
+    }
+}
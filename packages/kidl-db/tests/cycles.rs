@@ -0,0 +1,121 @@
+mod common;
+
+use common::{database, file, text};
+use kidl_db::{
+    cycles::file_type_graph,
+    diagnostics::{file_diagnostics, Code, Severity},
+    Database,
+};
+
+/// Infinite size diagnostics of the file at `relative`, with the text
+/// they point at.
+fn infinite_size(db: &Database, relative: &str) -> Vec<(Severity, String, String)> {
+    let file = file(db, relative);
+    file_diagnostics(db, file)
+        .into_iter()
+        .filter(|diagnostic| diagnostic.code == Some(Code::InfiniteSize))
+        .map(|diagnostic| {
+            let range = text(db, file, &diagnostic.range);
+            (diagnostic.severity, diagnostic.message, range)
+        })
+        .collect()
+}
+
+#[test]
+fn self_reference() {
+    let db = database(&[("main.kidl", "struct A {\n    a: A,\n    b: string,\n}\n")]);
+
+    assert_eq!(
+        infinite_size(&db, "main.kidl"),
+        [
+            (
+                Severity::Error,
+                "Struct `A` has infinite size, it contains itself: A.a -> A".to_owned(),
+                "A".to_owned(),
+            ),
+            (
+                Severity::Hint,
+                "Make `a` optional to break the cycle, it will be boxed in generated code"
+                    .to_owned(),
+                "a: A".to_owned(),
+            ),
+        ]
+    );
+}
+
+#[test]
+fn mutual_recursion_across_imports() {
+    let db = database(&[
+        ("a.kidl", "use b::B;\n\nstruct A {\n    b: B,\n}\n"),
+        (
+            "b.kidl",
+            "use a::A;\n\nstruct B {\n    list: List<A>,\n    a: A,\n}\n",
+        ),
+    ]);
+
+    let messages = |relative: &str| {
+        infinite_size(&db, relative)
+            .into_iter()
+            .map(|(_, message, range)| (message, range))
+            .collect::<Vec<_>>()
+    };
+    assert_eq!(
+        messages("a.kidl"),
+        [
+            (
+                "Struct `A` has infinite size, it contains itself: A.b -> B.a -> A".to_owned(),
+                "A".to_owned(),
+            ),
+            (
+                "Make `b` optional to break the cycle, it will be boxed in generated code"
+                    .to_owned(),
+                "b: B".to_owned(),
+            ),
+        ]
+    );
+    assert_eq!(
+        messages("b.kidl"),
+        [
+            (
+                "Struct `B` has infinite size, it contains itself: B.a -> A.b -> B".to_owned(),
+                "B".to_owned(),
+            ),
+            (
+                "Make `a` optional to break the cycle, it will be boxed in generated code"
+                    .to_owned(),
+                "a: A".to_owned(),
+            ),
+        ]
+    );
+}
+
+#[test]
+fn cycles_broken_by_option_and_list() {
+    let db = database(&[(
+        "main.kidl",
+        "struct A {\n    a: Option<A>,\n}\n\nstruct B {\n    b?: B,\n}\n\nstruct C {\n    d: D,\n}\n\nstruct D {\n    c: List<C>,\n}\n",
+    )]);
+    let main = file(&db, "main.kidl");
+
+    assert!(infinite_size(&db, "main.kidl").is_empty());
+
+    // `List` breaks containment, `Option` and optional fields only make
+    // the cycle finite
+    let graph = file_type_graph(&db, main);
+    let names = graph
+        .recursive_components()
+        .iter()
+        .map(|component| {
+            component
+                .iter()
+                .map(|def| def.name(&db).text(&db).clone())
+                .collect::<Vec<_>>()
+        })
+        .collect::<Vec<_>>();
+    assert_eq!(names, [["A"], ["B"]]);
+    assert!(graph.infinite_components().is_empty());
+    assert!(graph
+        .edges()
+        .iter()
+        .all(|edge| !edge.required || edge.to.name(&db).text(&db) == "D"));
+}
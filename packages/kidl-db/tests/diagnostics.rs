@@ -0,0 +1,30 @@
+mod common;
+
+use common::{database, messages};
+
+#[test]
+fn diagnostics_stay_in_their_file() {
+    let db = database(&[
+        (
+            "main.kidl",
+            "use other::Other;\n\nstruct A {\n    a: Other,\n}\n",
+        ),
+        (
+            "other.kidl",
+            "struct Other {\n    a: Missing,\n}\n\nstruct Broken {\n    a:\n",
+        ),
+    ]);
+
+    // Checking `main.kidl` lowers and resolves `other.kidl` too
+    assert_eq!(messages(&db, "main.kidl"), Vec::<String>::new());
+
+    let other = messages(&db, "other.kidl");
+    assert!(
+        other.contains(&"Unresolved type `Missing`".to_owned()),
+        "{:?}",
+        other
+    );
+    assert!(other.len() > 1, "no syntax error in {:?}", other);
+    // Also once the imported file was checked itself
+    assert_eq!(messages(&db, "main.kidl"), Vec::<String>::new());
+}
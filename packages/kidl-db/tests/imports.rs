@@ -0,0 +1,99 @@
+mod common;
+
+use std::{
+    collections::BTreeMap,
+    path::{Path, PathBuf},
+};
+
+use common::{database, file, path};
+use kidl_db::{
+    imports::{import_module, module_path},
+    ir::lower_file,
+    Database,
+};
+
+/// Imports `target` into `from` with the module path [`import_module`]
+/// picks, then resolves that path again.
+fn round_trip(db: &mut Database, from: &str, target: &Path) -> (String, Option<PathBuf>) {
+    let importer = file(db, from);
+    let target = db.schema_file(target).unwrap();
+    let module = import_module(&*db, importer, target);
+
+    db.push_file(path(from), format!("use {}::Item;\n", module));
+    let item = lower_file(&*db, importer).uses(&*db)[0];
+    (module, module_path(&*db, item))
+}
+
+#[test]
+fn module_paths_round_trip() {
+    let mut db = database(&[
+        ("main.kidl", ""),
+        ("nested/deep/file.kidl", ""),
+        ("a/b.kidl", ""),
+        ("c.kidl", ""),
+        ("my-types.kidl", ""),
+        ("third_party/dom/events.kidl", ""),
+        ("lib/shared.kidl", ""),
+        ("dom/clash.kidl", ""),
+    ]);
+    db.push_file(PathBuf::from("/elsewhere/x.kidl"), String::new());
+    db.set_aliases(BTreeMap::from([
+        ("dom".to_owned(), path("third_party/dom")),
+        ("shared".to_owned(), path("lib/shared.kidl")),
+    ]));
+
+    let cases = [
+        ("main.kidl", path("a/b.kidl"), "a::b"),
+        // Identifier paths start at the root, not at the importing file
+        ("nested/deep/file.kidl", path("c.kidl"), "c"),
+        (
+            "main.kidl",
+            path("third_party/dom/events.kidl"),
+            "dom::events",
+        ),
+        // An alias can name a file
+        ("main.kidl", path("lib/shared.kidl"), "shared"),
+        ("main.kidl", path("my-types.kidl"), "\"./my-types.kidl\""),
+        // `dom::clash` would start from the alias
+        ("main.kidl", path("dom/clash.kidl"), "\"./dom/clash.kidl\""),
+        (
+            "nested/deep/file.kidl",
+            PathBuf::from("/elsewhere/x.kidl"),
+            "\"../../../elsewhere/x.kidl\"",
+        ),
+        (
+            "nested/deep/file.kidl",
+            path("my-types.kidl"),
+            "\"../../my-types.kidl\"",
+        ),
+    ];
+
+    for (from, target, expected) in cases {
+        let (module, resolved) = round_trip(&mut db, from, &target);
+        assert_eq!(module, expected, "importing {:?} into {}", target, from);
+        assert_eq!(resolved, Some(target), "resolving `{}` in {}", module, from);
+    }
+}
+
+#[test]
+fn module_path_of_use_items() {
+    let db = database(&[(
+        "a/main.kidl",
+        "use b::c::D;\nuse \"../e.kidl\"::E;\nuse \"./f\"::g::H;\nuse I;\n",
+    )]);
+    let uses = lower_file(&db, file(&db, "a/main.kidl")).uses(&db);
+
+    let paths = uses
+        .iter()
+        .map(|item| module_path(&db, *item))
+        .collect::<Vec<_>>();
+    assert_eq!(
+        paths,
+        [
+            Some(path("b/c.kidl")),
+            Some(path("e.kidl")),
+            Some(path("a/f/g.kidl")),
+            None,
+        ]
+    );
+}
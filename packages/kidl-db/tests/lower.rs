@@ -0,0 +1,209 @@
+mod common;
+
+use common::{database, file, messages, text};
+use kidl_db::ir::{lower_file, PathSegment};
+
+#[test]
+fn structs_and_fields() {
+    let source = "// Users\nstruct User {\n    name: string,\n    tags?: Map<string, List<Tag>>, // Labels\n}\n";
+    let db = database(&[("main.kidl", source)]);
+    let main = file(&db, "main.kidl");
+    let module = lower_file(&db, main);
+
+    let [user] = module.structs(&db).as_slice() else {
+        panic!("{:?}", module.structs(&db));
+    };
+    assert_eq!(user.name(&db).text(&db), "User");
+    assert_eq!(user.file(&db), main);
+    assert_eq!(text(&db, main, &user.name_span(&db)), "User");
+    // Comments and whitespace around the item are left out
+    assert_eq!(
+        text(&db, main, &user.span(&db)),
+        "struct User {\n    name: string,\n    tags?: Map<string, List<Tag>>, // Labels\n}"
+    );
+
+    let [name, tags] = user.fields(&db).as_slice() else {
+        panic!("{:?}", user.fields(&db));
+    };
+    assert_eq!(name.name(&db).text(&db), "name");
+    assert!(!name.optional(&db));
+    assert_eq!(text(&db, main, &name.span(&db)), "name: string");
+    assert_eq!(name.ty(&db).as_ref().unwrap().text(&db), "string");
+
+    assert!(tags.optional(&db));
+    assert_eq!(text(&db, main, &tags.name_span(&db)), "tags");
+    assert_eq!(
+        text(&db, main, &tags.span(&db)),
+        "tags?: Map<string, List<Tag>>"
+    );
+
+    let ty = tags.ty(&db).as_ref().unwrap();
+    assert_eq!(ty.text(&db), "Map<string, List<Tag>>");
+    assert_eq!(text(&db, main, &ty.span), "Map<string, List<Tag>>");
+    assert_eq!(text(&db, main, &ty.name_span(&db)), "Map");
+    assert_eq!(text(&db, main, &ty.args[1].span), "List<Tag>");
+    assert_eq!(text(&db, main, &ty.args[1].args[0].span), "Tag");
+}
+
+#[test]
+fn use_paths() {
+    let source = "use a::b::C;\nuse \"../d.kidl\"::D;\n";
+    let db = database(&[("main.kidl", source)]);
+    let main = file(&db, "main.kidl");
+
+    let [ident, string] = lower_file(&db, main).uses(&db).as_slice() else {
+        panic!("expected two uses");
+    };
+
+    assert_eq!(text(&db, main, &ident.span(&db)), "use a::b::C;");
+    let segments = ident
+        .path(&db)
+        .iter()
+        .map(|segment| match segment {
+            PathSegment::Ident(name, _) => name.text(&db).clone(),
+            PathSegment::String(text, _) => text.clone(),
+        })
+        .collect::<Vec<_>>();
+    assert_eq!(segments, ["a", "b", "C"]);
+    assert_eq!(ident.imported(&db).unwrap().text(&db), "C");
+
+    let PathSegment::String(path, span) = &string.path(&db)[0] else {
+        panic!("{:?}", string.path(&db));
+    };
+    assert_eq!(path, "../d.kidl");
+    assert_eq!(text(&db, main, span), "\"../d.kidl\"");
+    assert_eq!(string.imported(&db).unwrap().text(&db), "D");
+}
+
+#[test]
+fn items_with_syntax_errors() {
+    let source = "struct {\n    a: A,\n}\n\nstruct B {\n    b:,\n    c: C\n}\n";
+    let db = database(&[("main.kidl", source)]);
+    let module = lower_file(&db, file(&db, "main.kidl"));
+
+    // The struct without a name is left out
+    let [b] = module.structs(&db).as_slice() else {
+        panic!("{:?}", module.structs(&db));
+    };
+    assert_eq!(b.name(&db).text(&db), "B");
+
+    let fields = b.fields(&db);
+    assert_eq!(fields.len(), 2);
+    assert_eq!(fields[0].ty(&db), &None);
+    assert_eq!(fields[1].ty(&db).as_ref().unwrap().text(&db), "C");
+}
+
+#[test]
+fn webidl_definitions() {
+    let source = r#"
+interface Event {
+  readonly attribute DOMString type;
+  readonly attribute EventTarget? target;
+};
+
+partial interface Event {
+  attribute sequence<long> path;
+};
+
+enum Phase { "none", "capturing" };
+
+typedef (DOMString or long) Key;
+typedef record<DOMString, Event?> Events;
+"#;
+    let db = database(&[("dom.webidl", source)]);
+    let dom = file(&db, "dom.webidl");
+    let module = lower_file(&db, dom);
+
+    let [event] = module.structs(&db).as_slice() else {
+        panic!("{:?}", module.structs(&db));
+    };
+    assert_eq!(text(&db, dom, &event.name_span(&db)), "Event");
+    let fields = event
+        .fields(&db)
+        .iter()
+        .map(|field| {
+            let ty = field.ty(&db).as_ref().map(|ty| ty.text(&db));
+            (field.name(&db).text(&db).clone(), field.optional(&db), ty)
+        })
+        .collect::<Vec<_>>();
+    assert_eq!(
+        fields,
+        [
+            ("type".to_owned(), false, Some("string".to_owned())),
+            // Not declared in the file
+            ("target".to_owned(), true, None),
+            ("path".to_owned(), false, Some("List<i32>".to_owned())),
+        ]
+    );
+
+    let [phase] = module.enums(&db).as_slice() else {
+        panic!("{:?}", module.enums(&db));
+    };
+    let variants = phase
+        .variants(&db)
+        .iter()
+        .map(|variant| {
+            (
+                variant.name.text(&db).clone(),
+                text(&db, dom, &variant.span),
+            )
+        })
+        .collect::<Vec<_>>();
+    assert_eq!(
+        variants,
+        [
+            ("none".to_owned(), "\"none\"".to_owned()),
+            ("capturing".to_owned(), "\"capturing\"".to_owned()),
+        ]
+    );
+
+    let [key, events] = module.aliases(&db).as_slice() else {
+        panic!("{:?}", module.aliases(&db));
+    };
+    // Unions have no KIDL counterpart
+    assert_eq!(key.ty(&db), &None);
+    assert_eq!(
+        events.ty(&db).as_ref().unwrap().text(&db),
+        "Map<string, Option<Event>>"
+    );
+}
+
+#[test]
+fn webidl_types_without_counterpart() {
+    let source = r#"
+[Exposed=*]
+interface AbortSignal : EventTarget {
+  [NewObject] static AbortSignal abort(optional any reason);
+  [NewObject] static AbortSignal _any(sequence<AbortSignal> signals);
+
+  readonly attribute boolean aborted;
+  readonly attribute any reason;
+  [Throws] undefined throwIfAborted();
+
+  attribute EventHandler onabort;
+};
+"#;
+    let db = database(&[("AbortSignal.webidl", source)]);
+    let module = lower_file(&db, file(&db, "AbortSignal.webidl"));
+
+    let [signal] = module.structs(&db).as_slice() else {
+        panic!("{:?}", module.structs(&db));
+    };
+    let fields = signal
+        .fields(&db)
+        .iter()
+        .map(|field| {
+            let ty = field.ty(&db).as_ref().map(|ty| ty.text(&db));
+            (field.name(&db).text(&db).clone(), ty)
+        })
+        .collect::<Vec<_>>();
+    assert_eq!(
+        fields,
+        [
+            ("aborted".to_owned(), Some("bool".to_owned())),
+            ("reason".to_owned(), None),
+            ("onabort".to_owned(), None),
+        ]
+    );
+    assert!(messages(&db, "AbortSignal.webidl").is_empty());
+}
@@ -0,0 +1,175 @@
+mod common;
+
+use std::path::PathBuf;
+
+use common::{temp_dir, write_files};
+use kidl_db::{
+    manifest::{Manifest, Project, Separator, MANIFEST},
+    Database,
+};
+use kidl_syn::parser::ListSeparators;
+
+const FILES: [(&str, &str); 7] = [
+    ("schemas/a.kidl", ""),
+    ("schemas/nested/b.kidl", ""),
+    ("schemas/legacy/old.kidl", ""),
+    ("schemas/notes.txt", ""),
+    ("schemas/.hidden/c.kidl", ""),
+    (".kidl/vendor/d.kidl", ""),
+    ("other/e.kidl", ""),
+];
+
+fn relative_files(project: &Project) -> Vec<PathBuf> {
+    let (files, errors) = project.files().unwrap();
+    assert!(errors.is_empty(), "{:?}", errors);
+    files
+        .into_iter()
+        .map(|path| path.strip_prefix(&project.root).unwrap().to_owned())
+        .collect()
+}
+
+#[test]
+fn default_manifest() {
+    let dir = temp_dir("default-manifest");
+    write_files(&dir, &FILES);
+
+    // Without a manifest the directory is the project
+    let project = Project::discover(&dir).unwrap();
+    assert_eq!(project.root, dir);
+    assert_eq!(project.manifest, Manifest::default());
+
+    // Hidden directories are skipped
+    assert_eq!(
+        relative_files(&project),
+        [
+            PathBuf::from("other/e.kidl"),
+            PathBuf::from("schemas/a.kidl"),
+            PathBuf::from("schemas/legacy/old.kidl"),
+            PathBuf::from("schemas/nested/b.kidl"),
+        ]
+    );
+}
+
+#[test]
+fn include_and_exclude() {
+    let dir = temp_dir("include-exclude");
+    write_files(&dir, &FILES);
+    write_files(
+        &dir,
+        &[(
+            MANIFEST,
+            r#"
+[workspace]
+roots = ["schemas"]
+include = ["**/*.kidl", "**/*.txt"]
+exclude = ["schemas/legacy/**"]
+
+[imports]
+dom = "third_party/dom.webidl"
+"#,
+        )],
+    );
+
+    // The manifest is found from a subdirectory
+    let project = Project::discover(&dir.join("schemas/nested")).unwrap();
+    assert_eq!(project.root, dir);
+    assert_eq!(
+        relative_files(&project),
+        [
+            PathBuf::from("schemas/a.kidl"),
+            PathBuf::from("schemas/nested/b.kidl"),
+            PathBuf::from("schemas/notes.txt"),
+        ]
+    );
+    assert_eq!(
+        project.aliases().get("dom"),
+        Some(&dir.join("third_party/dom.webidl"))
+    );
+}
+
+#[test]
+fn syntax_options() {
+    let dir = temp_dir("syntax-options");
+    write_files(
+        &dir,
+        &[(
+            MANIFEST,
+            "[syntax]\nfield_separators = [\"semicolon\", \"newline\"]\n",
+        )],
+    );
+
+    let manifest = Manifest::load(&dir.join(MANIFEST)).unwrap();
+    assert_eq!(
+        manifest.syntax.field_separators,
+        [Separator::Semicolon, Separator::Newline]
+    );
+    assert_eq!(
+        manifest.syntax.parse_options().field_separators,
+        ListSeparators {
+            comma: false,
+            semicolon: true,
+            newline: true,
+        }
+    );
+    assert_eq!(
+        Manifest::default().syntax.parse_options().field_separators,
+        ListSeparators::COMMA
+    );
+}
+
+#[test]
+fn invalid_manifests() {
+    let dir = temp_dir("invalid-manifests");
+    let path = dir.join(MANIFEST);
+
+    for text in [
+        "[workspace]\nroot = [\"schemas\"]\n",
+        "[workspace]\nexclude = \"legacy\"\n",
+        "[syntax]\nfield_separators = [\"tab\"]\n",
+        "[syntax]\nfield_separators = []\n",
+    ] {
+        write_files(&dir, &[(MANIFEST, text)]);
+        let error = Manifest::load(&path).unwrap_err();
+        assert_eq!(error.path, path, "{}", text);
+    }
+
+    // Bad globs are reported when the files are listed
+    write_files(&dir, &[(MANIFEST, "[workspace]\ninclude = [\"[\"]\n")]);
+    let project = Project::discover(&dir).unwrap();
+    assert_eq!(project.files().unwrap_err().path, path);
+}
+
+#[test]
+fn unreadable_entries_are_skipped() {
+    let dir = temp_dir("unreadable-entries");
+    write_files(
+        &dir,
+        &[
+            ("schemas/a.kidl", "struct A {}\n"),
+            (
+                MANIFEST,
+                "[workspace]\nroots = [\"schemas\", \"missing\"]\n",
+            ),
+        ],
+    );
+    // Not UTF-8
+    std::fs::write(dir.join("schemas/b.kidl"), [0xff, 0xfe]).unwrap();
+
+    let project = Project::discover(&dir).unwrap();
+    let (files, errors) = project.files().unwrap();
+    assert_eq!(
+        files,
+        [dir.join("schemas/a.kidl"), dir.join("schemas/b.kidl")]
+    );
+    let paths = errors.iter().map(|e| e.path.clone()).collect::<Vec<_>>();
+    assert_eq!(paths, [dir.join("missing")]);
+
+    let mut db = Database::new(dir.clone());
+    let (loaded, errors) = db.load_project(&project).unwrap();
+    assert_eq!(
+        loaded,
+        [db.schema_file(&dir.join("schemas/a.kidl")).unwrap()]
+    );
+    let paths = errors.iter().map(|e| e.path.clone()).collect::<Vec<_>>();
+    assert_eq!(paths, [dir.join("missing"), dir.join("schemas/b.kidl")]);
+}
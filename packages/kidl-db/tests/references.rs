@@ -0,0 +1,68 @@
+mod common;
+
+use common::{database, file};
+use kidl_db::{
+    ir::{lower_file, Item, SchemaFile},
+    references::{file_references, reference_index, Reference, ReferenceKind},
+    Db,
+};
+
+const B: &str = "use a::Tag;\n\nstruct B {\n    tag: Tag,\n}\n";
+const MAIN: &str = "use a::Tag;\n\nstruct Post {\n    tags: List<Tag>,\n    by_name: Map<string, Option<Tag>>,\n    main: Tag,\n}\n";
+
+/// Start offsets of `Tag` in `source`.
+fn offsets(source: &str) -> Vec<usize> {
+    source
+        .match_indices("Tag")
+        .map(|(start, _)| start)
+        .collect()
+}
+
+fn describe(references: &[Reference]) -> Vec<(SchemaFile, usize, ReferenceKind)> {
+    references
+        .iter()
+        .map(|reference| (reference.file, reference.span.start.0, reference.kind))
+        .collect()
+}
+
+#[test]
+fn type_arguments_and_imports() {
+    let db = database(&[
+        ("a.kidl", "struct Tag {}\n"),
+        ("b.kidl", B),
+        ("main.kidl", MAIN),
+    ]);
+    let tag = Item::Struct(lower_file(&db, file(&db, "a.kidl")).structs(&db)[0]);
+
+    let b = file(&db, "b.kidl");
+    let [import, field] = offsets(B)[..] else {
+        panic!("{:?}", offsets(B));
+    };
+    let mut expected = vec![
+        (b, import, ReferenceKind::Import),
+        (b, field, ReferenceKind::FieldType),
+    ];
+
+    let main = file(&db, "main.kidl");
+    let [import, list, option, field] = offsets(MAIN)[..] else {
+        panic!("{:?}", offsets(MAIN));
+    };
+    let in_main = [
+        (main, import, ReferenceKind::Import),
+        (main, list, ReferenceKind::TypeArgument),
+        (main, option, ReferenceKind::TypeArgument),
+        (main, field, ReferenceKind::FieldType),
+    ];
+
+    let references = file_references(&db, main)
+        .iter()
+        .filter(|(item, _)| *item == tag)
+        .map(|(_, reference)| reference.clone())
+        .collect::<Vec<_>>();
+    assert_eq!(describe(&references), in_main);
+
+    // Grouped by file, in source order
+    expected.extend(in_main);
+    let index = reference_index(&db, db.workspace());
+    assert_eq!(describe(index.references(tag)), expected);
+}
@@ -0,0 +1,124 @@
+mod common;
+
+use common::{database, file, text};
+use kidl_db::{
+    diagnostics::{file_diagnostics, Code, Diagnostic},
+    imports::imported_item,
+    ir::{lower_file, Item, Name},
+    prelude::Builtin,
+    resolve::{resolve_type_ref, Resolution},
+    Database,
+};
+
+fn resolve(db: &Database, path: &str, name: &str) -> Option<Resolution> {
+    resolve_type_ref(db, file(db, path), Name::new(db, name.to_owned()))
+}
+
+#[test]
+fn local_imported_and_builtin_names() {
+    let db = database(&[
+        (
+            "main.kidl",
+            "use other::Imported;\n\nstruct Local {\n    a: Imported,\n    b: Map<string, Local>,\n}\n",
+        ),
+        ("other.kidl", "struct Imported {}\n"),
+    ]);
+    let main = lower_file(&db, file(&db, "main.kidl"));
+
+    let local = Item::Struct(main.structs(&db)[0]);
+    assert_eq!(
+        resolve(&db, "main.kidl", "Local"),
+        Some(Resolution::Local(local))
+    );
+
+    let item = main.uses(&db)[0];
+    assert_eq!(
+        resolve(&db, "main.kidl", "Imported"),
+        Some(Resolution::Import(item))
+    );
+    let declaration = lower_file(&db, file(&db, "other.kidl")).structs(&db)[0];
+    assert_eq!(imported_item(&db, item), Some(Item::Struct(declaration)));
+
+    assert_eq!(
+        resolve(&db, "main.kidl", "string"),
+        Some(Resolution::Builtin(Builtin::String))
+    );
+    assert_eq!(
+        resolve(&db, "main.kidl", "Map"),
+        Some(Resolution::Builtin(Builtin::Map))
+    );
+
+    // Names are resolved per file
+    assert_eq!(resolve(&db, "other.kidl", "Local"), None);
+    assert!(file_diagnostics(&db, file(&db, "main.kidl")).is_empty());
+}
+
+#[test]
+fn local_declarations_shadow_builtins() {
+    let db = database(&[("main.kidl", "struct Map {}\n\nstruct A {\n    a: Map,\n}\n")]);
+    let map = lower_file(&db, file(&db, "main.kidl")).structs(&db)[0];
+
+    assert_eq!(
+        resolve(&db, "main.kidl", "Map"),
+        Some(Resolution::Local(Item::Struct(map)))
+    );
+}
+
+#[test]
+fn unresolved_names() {
+    let source = "struct A {\n    a: List<Missing>,\n}\n";
+    let db = database(&[("main.kidl", source)]);
+    let main = file(&db, "main.kidl");
+
+    assert_eq!(resolve(&db, "main.kidl", "Missing"), None);
+
+    let [diagnostic]: [Diagnostic; 1] = file_diagnostics(&db, main).try_into().unwrap();
+    assert_eq!(diagnostic.message, "Unresolved type `Missing`");
+    assert_eq!(text(&db, main, &diagnostic.range), "Missing");
+}
+
+#[test]
+fn duplicate_definitions() {
+    let source = "struct A {}\n\nstruct A {\n    a: string,\n}\n";
+    let db = database(&[("main.kidl", source)]);
+    let main = file(&db, "main.kidl");
+    let structs = lower_file(&db, main).structs(&db);
+
+    // The first declaration wins
+    assert_eq!(
+        resolve(&db, "main.kidl", "A"),
+        Some(Resolution::Local(Item::Struct(structs[0])))
+    );
+
+    let [diagnostic]: [Diagnostic; 1] = file_diagnostics(&db, main).try_into().unwrap();
+    assert_eq!(diagnostic.code, Some(Code::DuplicateDefinition));
+    assert_eq!(diagnostic.message, "Duplicate definition of `A`");
+    assert_eq!(diagnostic.range, structs[1].name_span(&db));
+}
+
+#[test]
+fn ambiguous_imports() {
+    let db = database(&[
+        (
+            "main.kidl",
+            "use a::X;\nuse b::X;\nuse a::X;\n\nstruct S {\n    x: X,\n}\n",
+        ),
+        ("a.kidl", "struct X {}\n"),
+        ("b.kidl", "struct X {}\n"),
+    ]);
+    let main = file(&db, "main.kidl");
+    let uses = lower_file(&db, main).uses(&db);
+
+    assert_eq!(
+        resolve(&db, "main.kidl", "X"),
+        Some(Resolution::Import(uses[0]))
+    );
+
+    // Importing the same path again is not ambiguous
+    let ambiguous = file_diagnostics(&db, main)
+        .into_iter()
+        .filter(|diagnostic| diagnostic.code == Some(Code::AmbiguousImport))
+        .map(|diagnostic| text(&db, main, &diagnostic.range))
+        .collect::<Vec<_>>();
+    assert_eq!(ambiguous, ["use b::X;"]);
+}
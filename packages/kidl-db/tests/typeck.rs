@@ -0,0 +1,78 @@
+mod common;
+
+use common::{database, messages};
+
+#[test]
+fn builtin_arity() {
+    let db = database(&[(
+        "main.kidl",
+        "struct A {\n    a: List<string, u32>,\n    b: string<u32>,\n    c: Map<string, List<u32>>,\n}\n",
+    )]);
+
+    assert_eq!(
+        messages(&db, "main.kidl"),
+        [
+            "Type `List` takes 1 type argument, but 2 were given",
+            "Type `string` doesn't take type arguments",
+        ]
+    );
+}
+
+#[test]
+fn declarations_take_no_arguments() {
+    let db = database(&[
+        (
+            "main.kidl",
+            "use other::Imported;\n\nstruct Local {}\n\nstruct A {\n    a: Local<string>,\n    b: Imported<Local>,\n}\n",
+        ),
+        ("other.kidl", "struct Imported {}\n"),
+    ]);
+
+    assert_eq!(
+        messages(&db, "main.kidl"),
+        [
+            "Type `Local` doesn't take type arguments",
+            "Type `Imported` doesn't take type arguments",
+        ]
+    );
+}
+
+#[test]
+fn map_keys() {
+    let dom = "enum Phase { \"none\", \"capturing\" };\n";
+    let db = database(&[
+        (
+            "main.kidl",
+            "use \"dom.webidl\"::Phase;\nuse other::Imported;\n\nstruct Local {}\n\nstruct A {\n    a: Map<string, Local>,\n    b: Map<Phase, Local>,\n    c: Map<Local, string>,\n    d: Map<Imported, string>,\n    e: Map<f64, string>,\n}\n",
+        ),
+        ("other.kidl", "struct Imported {}\n"),
+        ("dom.webidl", dom),
+    ]);
+
+    assert_eq!(
+        messages(&db, "main.kidl"),
+        [
+            "Type `Local` can't be used as a map key, expected a hashable primitive",
+            "Type `Imported` can't be used as a map key, expected a hashable primitive",
+            "Type `f64` can't be used as a map key, expected a hashable primitive",
+        ]
+    );
+}
+
+#[test]
+fn map_keys_through_aliases() {
+    let dom = "interface Event {};\ntypedef Event EventKey;\ntypedef DOMString EventName;\ntypedef EventName NameKey;\ntypedef Loop Cycle;\ntypedef Cycle Loop;\n";
+    let db = database(&[
+        (
+            "main.kidl",
+            "use \"dom.webidl\"::EventKey;\nuse \"dom.webidl\"::NameKey;\nuse \"dom.webidl\"::Cycle;\n\nstruct A {\n    a: Map<EventKey, string>,\n    b: Map<NameKey, string>,\n    c: Map<Cycle, string>,\n}\n",
+        ),
+        ("dom.webidl", dom),
+    ]);
+
+    // Aliases that refer to each other are cut off
+    assert_eq!(
+        messages(&db, "main.kidl"),
+        ["Type `EventKey` can't be used as a map key, expected a hashable primitive"]
+    );
+}
@@ -0,0 +1,148 @@
+mod common;
+
+use common::{temp_dir, write_files};
+use kidl_db::vendor::{
+    checksum, ImportResolver, LockedImport, Lockfile, VendorCache, LOCKFILE, VENDOR_DIR,
+};
+
+const URL: &str = "https://example.com/idl/Event.webidl";
+const OTHER_URL: &str = "https://example.com/idl/Node.webidl";
+
+fn locked(url: &str, contents: &str) -> LockedImport {
+    LockedImport {
+        url: url.to_owned(),
+        file: VendorCache::file_name(url),
+        sha256: checksum(contents.as_bytes()),
+    }
+}
+
+#[test]
+fn file_names() {
+    let name = VendorCache::file_name(URL);
+    assert_eq!(name.len(), "0123456789abcdef.webidl".len());
+    assert!(name.ends_with(".webidl"));
+    assert_ne!(name, VendorCache::file_name(OTHER_URL));
+
+    // Query strings don't make an extension
+    assert!(!VendorCache::file_name("https://example.com/idl?v=1.2&x").contains('.'));
+}
+
+#[test]
+fn lockfile_round_trip() {
+    let dir = temp_dir("lockfile-round-trip");
+    let path = dir.join(LOCKFILE);
+
+    assert_eq!(Lockfile::load(&path).unwrap(), Lockfile::default());
+
+    let mut lockfile = Lockfile::default();
+    lockfile.insert(locked(OTHER_URL, "interface Node {};"));
+    lockfile.insert(locked(URL, "old"));
+    lockfile.insert(locked(URL, "interface Event {};"));
+
+    // Sorted by URL, the second insert replaced the first
+    let urls = lockfile
+        .imports
+        .iter()
+        .map(|import| import.url.as_str())
+        .collect::<Vec<_>>();
+    assert_eq!(urls, [URL, OTHER_URL]);
+    assert_eq!(
+        lockfile.get(URL).unwrap().sha256,
+        checksum(b"interface Event {};")
+    );
+
+    lockfile.save(&path).unwrap();
+    assert_eq!(Lockfile::load(&path).unwrap(), lockfile);
+
+    write_files(&dir, &[(LOCKFILE, "[[import]]\nurl = \"a\"\n")]);
+    assert!(Lockfile::load(&path).is_err());
+}
+
+#[test]
+fn verifies_vendored_files() {
+    let dir = temp_dir("verifies-vendored-files");
+    let missing_url = "https://example.com/idl/Missing.webidl";
+
+    let mut lockfile = Lockfile::default();
+    lockfile.insert(locked(URL, "interface Event {};"));
+    lockfile.insert(locked(OTHER_URL, "interface Node {};"));
+    lockfile.insert(locked(missing_url, ""));
+    lockfile.save(&dir.join(LOCKFILE)).unwrap();
+
+    let event = format!("{}/{}", VENDOR_DIR, VendorCache::file_name(URL));
+    let node = format!("{}/{}", VENDOR_DIR, VendorCache::file_name(OTHER_URL));
+    write_files(
+        &dir,
+        &[
+            (event.as_str(), "interface Event {};"),
+            // Changed after it was locked
+            (node.as_str(), "interface Node { attribute long x; };"),
+        ],
+    );
+
+    let cache = VendorCache::open(&dir).unwrap();
+    assert!(cache.is_vendored(URL));
+    assert!(!cache.is_vendored(OTHER_URL));
+    assert!(!cache.is_vendored(missing_url));
+    assert_eq!(cache.problems().len(), 2, "{:?}", cache.problems());
+
+    assert_eq!(
+        cache.resolve_url(URL),
+        Some(dir.join(VENDOR_DIR).join(VendorCache::file_name(URL)))
+    );
+    assert_eq!(cache.resolve_url(OTHER_URL), None);
+}
+
+#[test]
+fn stores_downloads() {
+    let dir = temp_dir("stores-downloads");
+    let mut cache = VendorCache::open(&dir).unwrap();
+    assert!(!cache.is_vendored(URL));
+
+    let path = cache.store(URL, b"interface Event {};").unwrap();
+    assert_eq!(std::fs::read(&path).unwrap(), b"interface Event {};");
+    assert!(cache.is_vendored(URL));
+    cache.save().unwrap();
+
+    let reopened = VendorCache::open(&dir).unwrap();
+    assert_eq!(
+        reopened.lockfile().get(URL),
+        Some(&locked(URL, "interface Event {};"))
+    );
+    assert_eq!(reopened.resolve_url(URL), Some(path));
+    assert!(reopened.problems().is_empty());
+}
+
+#[test]
+fn downloads_have_to_match_the_lockfile() {
+    let dir = temp_dir("downloads-match-lockfile");
+    let mut lockfile = Lockfile::default();
+    lockfile.insert(locked(URL, "interface Event {};"));
+    lockfile.save(&dir.join(LOCKFILE)).unwrap();
+
+    // Locked but not vendored yet, e.g. on a fresh checkout
+    let mut cache = VendorCache::open(&dir).unwrap();
+    assert!(!cache.is_vendored(URL));
+
+    let changed = b"interface Event { attribute long x; };";
+    let error = cache.store(URL, changed).unwrap_err();
+    assert_eq!(error.kind(), std::io::ErrorKind::InvalidData);
+    assert!(!cache.is_vendored(URL));
+    assert!(!dir
+        .join(VENDOR_DIR)
+        .join(VendorCache::file_name(URL))
+        .exists());
+    assert_eq!(
+        cache.lockfile().get(URL),
+        Some(&locked(URL, "interface Event {};"))
+    );
+
+    let path = cache.store(URL, b"interface Event {};").unwrap();
+    assert_eq!(std::fs::read(&path).unwrap(), b"interface Event {};");
+    assert!(cache.is_vendored(URL));
+
+    // Only an update replaces the entry
+    cache.update(URL, changed).unwrap();
+    assert_eq!(std::fs::read(&path).unwrap(), changed);
+    assert_eq!(cache.lockfile().get(URL).unwrap().sha256, checksum(changed));
+}
@@ -0,0 +1,455 @@
+use std::path::Path;
+
+use kidl_db::{
+    imports::{import_segments, imported_item, resolve_module_path, EXTENSION},
+    ir::{lower_file, Item, Name, PathSegment, SchemaFile},
+    prelude::Builtin,
+    resolve::{file_scope, Resolution},
+    vendor::VENDOR_DIR,
+    Database, Db,
+};
+use kidl_syn::{
+    ast::{make, schema::Schema, AstNode as _, SyntaxNode, SyntaxToken, TextSize},
+    helpers::ByteOffset,
+    kind::{NodeKind, SyntaxKind, TokenKind},
+    parser::is_identifier,
+};
+use lsp_types::{
+    CompletionItem, CompletionItemKind, CompletionOptions, CompletionParams, CompletionResponse,
+    TextEdit,
+};
+
+use crate::{
+    file_path,
+    position::{position_to_byte, span_to_range},
+};
+
+const KEYWORDS: [&str; 2] = ["use", "struct"];
+
+pub(crate) fn capabilities() -> CompletionOptions {
+    CompletionOptions {
+        trigger_characters: Some(vec![":".to_owned(), "<".to_owned()]),
+        ..Default::default()
+    }
+}
+
+/// What can be written at the cursor.
+#[derive(Debug)]
+enum Context {
+    /// The keyword starting a top-level item.
+    Item,
+    /// A type name, after `:` in a field or inside `<...>`.
+    Type,
+    /// A segment of a `use` path, after the given segments.
+    UsePath(Vec<SyntaxToken>),
+}
+
+pub fn completion(db: &Database, params: CompletionParams) -> Option<CompletionResponse> {
+    let position = &params.text_document_position;
+    let file = db.schema_file(&file_path(&position.text_document.uri)?)?;
+    let text = file.text(db);
+    if position.position.line as usize >= text.len_lines() {
+        return None;
+    }
+
+    let offset = position_to_byte(&text, position.position);
+    let root = SyntaxNode::new_root(kidl_db::source::parse(db, file));
+    let items = match context(&root, offset)? {
+        Context::Item => keywords(),
+        Context::Type => types(db, file),
+        Context::UsePath(segments) => use_segments(db, file, &segments),
+    };
+
+    Some(CompletionResponse::Array(items))
+}
+
+fn is_kind(token: &SyntaxToken, kind: TokenKind) -> bool {
+    token.kind() == SyntaxKind::Token(kind)
+}
+
+fn has_ancestor(token: &SyntaxToken, kind: NodeKind) -> bool {
+    token
+        .parent_ancestors()
+        .any(|node| node.kind() == SyntaxKind::Node(kind))
+}
+
+fn context(root: &SyntaxNode, offset: usize) -> Option<Context> {
+    let offset = TextSize::try_from(offset).ok()?;
+    let Some(left) = root.token_at_offset(offset).left_biased() else {
+        return Some(Context::Item);
+    };
+
+    // The name being typed doesn't count
+    let mut prev = match left.kind() {
+        SyntaxKind::Token(TokenKind::Ident) => left.prev_token(),
+        SyntaxKind::Token(TokenKind::String) => return None,
+        _ => Some(left.clone()),
+    };
+    // Spaces can contain line breaks, those end the line like a `NewLine`
+    let breaks_line = |token: &SyntaxToken| {
+        let range = token.text_range();
+        let len = offset.min(range.end()) - range.start();
+        token.text()[..usize::from(len)].contains('\n')
+    };
+    while let Some(token) = prev
+        .clone()
+        .filter(|t| is_kind(t, TokenKind::Space) && !breaks_line(t))
+    {
+        prev = token.prev_token();
+    }
+
+    let Some(prev) = prev else {
+        return Some(Context::Item);
+    };
+
+    match prev.kind() {
+        SyntaxKind::Token(TokenKind::Colon) if has_ancestor(&prev, NodeKind::Use) => {
+            let before = prev.prev_token()?;
+            let path = prev.parent()?;
+            if !is_kind(&before, TokenKind::Colon)
+                || path.kind() != SyntaxKind::Node(NodeKind::Path)
+            {
+                return None;
+            }
+
+            let segments = path
+                .children_with_tokens()
+                .filter_map(|element| element.into_token())
+                .filter(|token| token.text_range().end() <= before.text_range().start())
+                .filter(|token| {
+                    is_kind(token, TokenKind::Ident) || is_kind(token, TokenKind::String)
+                })
+                .collect();
+            Some(Context::UsePath(segments))
+        }
+        SyntaxKind::Token(TokenKind::Colon | TokenKind::AngleOpen) => Some(Context::Type),
+        SyntaxKind::Token(TokenKind::Comma) if has_ancestor(&prev, NodeKind::TypeArguments) => {
+            Some(Context::Type)
+        }
+        SyntaxKind::Token(TokenKind::Ident)
+            if prev.text() == "use" && has_ancestor(&prev, NodeKind::Use) =>
+        {
+            Some(Context::UsePath(Vec::new()))
+        }
+        SyntaxKind::Token(TokenKind::NewLine | TokenKind::Comment | TokenKind::Space)
+            if !in_struct_body(&prev, offset) =>
+        {
+            Some(Context::Item)
+        }
+        _ => None,
+    }
+}
+
+/// Whether `offset` is between the braces of the struct around `token`.
+fn in_struct_body(token: &SyntaxToken, offset: TextSize) -> bool {
+    let Some(node) = token
+        .parent_ancestors()
+        .find(|node| node.kind() == SyntaxKind::Node(NodeKind::Struct))
+    else {
+        return false;
+    };
+
+    let mut open = false;
+    for token in node.children_with_tokens().filter_map(|e| e.into_token()) {
+        if token.text_range().end() > offset {
+            break;
+        }
+
+        match token.kind() {
+            SyntaxKind::Token(TokenKind::CurlyOpen) => open = true,
+            SyntaxKind::Token(TokenKind::CurlyClose) => open = false,
+            _ => {}
+        }
+    }
+    open
+}
+
+fn keywords() -> Vec<CompletionItem> {
+    KEYWORDS
+        .into_iter()
+        .map(|keyword| CompletionItem {
+            label: keyword.to_owned(),
+            kind: Some(CompletionItemKind::KEYWORD),
+            ..Default::default()
+        })
+        .collect()
+}
+
+fn item_kind(item: Item) -> CompletionItemKind {
+    match item {
+        Item::Struct(_) | Item::Alias(_) => CompletionItemKind::STRUCT,
+        Item::Enum(_) => CompletionItemKind::ENUM,
+    }
+}
+
+fn item_completion(db: &Database, item: Item) -> CompletionItem {
+    CompletionItem {
+        label: item.name(db).text(db).clone(),
+        kind: Some(item_kind(item)),
+        detail: Some(display_path(db, item.file(db))),
+        ..Default::default()
+    }
+}
+
+/// Built-in types and the items in scope, then items of other files that
+/// are imported when picked.
+fn types(db: &Database, file: SchemaFile) -> Vec<CompletionItem> {
+    let scope = file_scope(db, file);
+    let mut items = Vec::new();
+
+    for builtin in Builtin::ALL {
+        if scope
+            .get(Name::new(db, builtin.name().to_owned()))
+            .is_none()
+        {
+            items.push(CompletionItem {
+                label: builtin.name().to_owned(),
+                kind: Some(CompletionItemKind::STRUCT),
+                detail: Some("built-in".to_owned()),
+                sort_text: Some(format!("0{}", builtin.name())),
+                ..Default::default()
+            });
+        }
+    }
+
+    for (name, resolution) in scope.iter() {
+        let item = match resolution {
+            Resolution::Local(item) => Some(item),
+            Resolution::Import(item) => imported_item(db, item),
+            Resolution::Builtin(_) => None,
+        };
+
+        let completion = match item {
+            Some(item) => item_completion(db, item),
+            None => CompletionItem {
+                label: name.text(db).clone(),
+                ..Default::default()
+            },
+        };
+        items.push(CompletionItem {
+            sort_text: Some(format!("0{}", completion.label)),
+            ..completion
+        });
+    }
+
+    let vendor_dir = db.workspace().root(db).join(VENDOR_DIR);
+    for other in db.schema_files() {
+        if other == file || other.path(db).starts_with(&vendor_dir) {
+            continue;
+        }
+
+        for item in lower_file(db, other).items(db) {
+            let name = item.name(db);
+            if scope.get(name).is_some() || Builtin::from_name(name.text(db)).is_some() {
+                continue;
+            }
+
+            let completion = item_completion(db, item);
+            items.push(CompletionItem {
+                sort_text: Some(format!("1{}", completion.label)),
+                additional_text_edits: Some(vec![import_edit(db, file, other, name)]),
+                ..completion
+            });
+        }
+    }
+
+    items
+}
+
+/// Adds `use module::Name;` after the last `use` item of `file`, or at the
+/// top if there is none.
+fn import_edit(db: &Database, file: SchemaFile, target: SchemaFile, name: Name) -> TextEdit {
+    let segments = import_segments(db, file, target);
+    let item = make::use_(
+        segments
+            .iter()
+            .map(String::as_str)
+            .chain([name.text(db).as_str()]),
+    );
+
+    let text = file.text(db);
+    let root = SyntaxNode::new_root(kidl_db::source::parse(db, file)).clone_for_update();
+    let schema = Schema::cast(root).unwrap();
+    schema.add_use(item);
+
+    // Only text was inserted, it starts where the old and new text differ
+    let old = text.to_string();
+    let new = schema.syntax().to_string();
+    let start = old
+        .char_indices()
+        .zip(new.chars())
+        .find(|((_, old), new)| old != new)
+        .map_or(old.len(), |((index, _), _)| index);
+    let inserted = &new[start..start + new.len() - old.len()];
+
+    TextEdit {
+        range: span_to_range(&text, &(ByteOffset(start)..ByteOffset(start))),
+        new_text: inserted.to_owned(),
+    }
+}
+
+/// Aliases and files of the workspace for the first segment of a `use`
+/// path, the files and directories of a module or the items of a file for
+/// the next ones.
+fn use_segments(db: &Database, file: SchemaFile, segments: &[SyntaxToken]) -> Vec<CompletionItem> {
+    let workspace = db.workspace();
+    let mut items = Vec::new();
+
+    let dir = match segments {
+        [] => {
+            for alias in workspace.aliases(db).keys() {
+                push_module(&mut items, alias);
+            }
+            workspace.root(db).clone()
+        }
+        _ => {
+            let module = segments
+                .iter()
+                .map(|token| {
+                    let range = token.text_range();
+                    let span = ByteOffset(range.start().into())..ByteOffset(range.end().into());
+                    match token.kind() {
+                        SyntaxKind::Token(TokenKind::String) => PathSegment::String(
+                            token.text().trim_matches(['"', '\'']).to_owned(),
+                            span,
+                        ),
+                        _ => PathSegment::Ident(Name::new(db, token.text().to_owned()), span),
+                    }
+                })
+                .collect::<Vec<_>>();
+
+            let Some(path) = resolve_module_path(db, file, &module) else {
+                return items;
+            };
+
+            if let Some(target) = workspace.file(db, &path) {
+                items.extend(
+                    lower_file(db, target)
+                        .items(db)
+                        .into_iter()
+                        .map(|item| item_completion(db, item)),
+                );
+            }
+
+            match module.last() {
+                Some(PathSegment::Ident(..)) => path.with_extension(""),
+                _ => path,
+            }
+        }
+    };
+
+    for path in workspace.files(db).keys() {
+        let Ok(relative) = path.strip_prefix(&dir) else {
+            continue;
+        };
+
+        let mut components = relative.components();
+        let (Some(first), rest) = (components.next(), components.next()) else {
+            continue;
+        };
+
+        let first = Path::new(first.as_os_str());
+        let name = match rest {
+            Some(_) => first.to_str(),
+            None if first.extension().is_some_and(|e| e == EXTENSION) => {
+                first.file_stem().and_then(|stem| stem.to_str())
+            }
+            None => None,
+        };
+
+        if let Some(name) = name.filter(|name| is_identifier(name)) {
+            push_module(&mut items, name);
+        }
+    }
+
+    items
+}
+
+fn push_module(items: &mut Vec<CompletionItem>, name: &str) {
+    if items.iter().any(|item| item.label == name) {
+        return;
+    }
+
+    items.push(CompletionItem {
+        label: name.to_owned(),
+        kind: Some(CompletionItemKind::MODULE),
+        ..Default::default()
+    });
+}
+
+fn display_path(db: &Database, file: SchemaFile) -> String {
+    db.workspace().display_path(db, file.path(db))
+}
+
+#[cfg(test)]
+mod tests {
+    use std::path::PathBuf;
+
+    use kidl_syn::parser::parse_str;
+
+    use super::*;
+    use crate::position::position_to_byte;
+
+    /// The context at `|` in `text`, with the segments of `use` paths.
+    fn context_at(text: &str) -> String {
+        let offset = text.find('|').unwrap();
+        let root = SyntaxNode::new_root(parse_str(&text.replace('|', "")).schema);
+        match context(&root, offset) {
+            Some(Context::UsePath(segments)) => {
+                let segments = segments.iter().map(|t| t.text()).collect::<Vec<_>>();
+                format!("UsePath({})", segments.join("::"))
+            }
+            Some(context) => format!("{:?}", context),
+            None => "None".to_owned(),
+        }
+    }
+
+    #[test]
+    fn contexts() {
+        assert_eq!(context_at("struct A {\n    a: |\n}\n"), "Type");
+        assert_eq!(context_at("struct A {\n    a: List<|\n}\n"), "Type");
+        assert_eq!(context_at("struct A {\n    a: Map<K, |\n}\n"), "Type");
+        assert_eq!(context_at("struct A {\n    a: Map<K, Li|\n}\n"), "Type");
+        assert_eq!(context_at("use |"), "UsePath()");
+        assert_eq!(context_at("use a::|"), "UsePath(a)");
+        assert_eq!(context_at("use a::b::C|"), "UsePath(a::b)");
+        assert_eq!(context_at("use a::B;\n|\nstruct A {}\n"), "Item");
+        assert_eq!(context_at("use a::B;\n\nst|"), "Item");
+        assert_eq!(context_at("struct A {\n    |\n}\n"), "None");
+    }
+
+    /// The text of `main.kidl` after importing `B` from `target`.
+    fn import(main: &str, target: &str) -> String {
+        let mut db = Database::new(PathBuf::from("/project"));
+        db.push_file(PathBuf::from("/project/main.kidl"), main.to_owned());
+        db.push_file(PathBuf::from(target), "struct B {}\n".to_owned());
+
+        let file = db.schema_file(Path::new("/project/main.kidl")).unwrap();
+        let target = db.schema_file(Path::new(target)).unwrap();
+        let edit = import_edit(&db, file, target, Name::new(&db, "B".to_owned()));
+
+        let text = file.text(&db);
+        assert_eq!(edit.range.start, edit.range.end);
+        let offset = position_to_byte(&text, edit.range.start);
+        let mut main = main.to_owned();
+        main.insert_str(offset, &edit.new_text);
+        main
+    }
+
+    #[test]
+    fn import_edits() {
+        assert_eq!(
+            import("struct A {}\n", "/project/a/b.kidl"),
+            "use a::b::B;\n\nstruct A {}\n"
+        );
+        assert_eq!(import("", "/project/b.kidl"), "use b::B;\n");
+        assert_eq!(
+            import("use c::C;\n\nstruct A {}\n", "/project/b.kidl"),
+            "use c::C;\nuse b::B;\n\nstruct A {}\n"
+        );
+        assert_eq!(
+            import("struct A {}\n", "/elsewhere/b.kidl"),
+            "use \"../elsewhere/b.kidl\"::B;\n\nstruct A {}\n"
+        );
+    }
+}
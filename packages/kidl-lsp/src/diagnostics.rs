@@ -0,0 +1,257 @@
+use std::{
+    collections::{hash_map::DefaultHasher, BTreeSet, HashMap},
+    error::Error,
+    hash::{Hash, Hasher},
+    time::{Duration, Instant},
+};
+
+use kidl_db::{
+    diagnostics::{file_diagnostics, Code, Diagnostic, Severity},
+    Database,
+};
+use lsp_server::{Connection, Message, Notification};
+use lsp_types::{
+    notification::{Notification as _, PublishDiagnostics},
+    DiagnosticOptions, DiagnosticServerCapabilities, DiagnosticSeverity, DiagnosticTag,
+    DocumentDiagnosticParams, DocumentDiagnosticReport, DocumentDiagnosticReportResult,
+    FullDocumentDiagnosticReport, NumberOrString, PublishDiagnosticsParams,
+    RelatedFullDocumentDiagnosticReport, RelatedUnchangedDocumentDiagnosticReport,
+    UnchangedDocumentDiagnosticReport, Url, WorkspaceDiagnosticParams, WorkspaceDiagnosticReport,
+    WorkspaceDiagnosticReportResult, WorkspaceDocumentDiagnosticReport,
+    WorkspaceFullDocumentDiagnosticReport, WorkspaceUnchangedDocumentDiagnosticReport,
+};
+use ropey::Rope;
+
+use crate::{file_path, position::span_to_range};
+
+/// Time without edits before diagnostics are computed again.
+const DEBOUNCE: Duration = Duration::from_millis(200);
+
+pub(crate) fn capabilities() -> DiagnosticServerCapabilities {
+    DiagnosticServerCapabilities::Options(DiagnosticOptions {
+        identifier: Some("kidl".to_owned()),
+        inter_file_dependencies: true,
+        workspace_diagnostics: true,
+        ..Default::default()
+    })
+}
+
+/// Publishes the diagnostics of open documents. An edit in one file can
+/// change the diagnostics of the files importing it, so all of them are
+/// published again after each change.
+///
+/// Clients that pull diagnostics don't get them pushed as well, the
+/// publisher only keeps track of open documents then.
+#[derive(Debug)]
+pub struct Publisher {
+    push: bool,
+    open: BTreeSet<Url>,
+    deadline: Option<Instant>,
+}
+
+impl Publisher {
+    pub fn new(push: bool) -> Self {
+        Publisher {
+            push,
+            open: BTreeSet::new(),
+            deadline: None,
+        }
+    }
+
+    pub fn open(&mut self, uri: Url) {
+        self.open.insert(uri);
+        self.changed();
+    }
+
+    pub fn changed(&mut self) {
+        if self.push {
+            self.deadline = Some(Instant::now() + DEBOUNCE);
+        }
+    }
+
+    /// Clears the diagnostics of a closed document.
+    pub fn close(
+        &mut self,
+        connection: &Connection,
+        uri: Url,
+    ) -> Result<(), Box<dyn Error + Sync + Send>> {
+        self.open.remove(&uri);
+        match self.push {
+            true => send(connection, uri, Vec::new()),
+            false => Ok(()),
+        }
+    }
+
+    /// Time left until the pending diagnostics are due, if any.
+    pub fn timeout(&self) -> Option<Duration> {
+        self.deadline
+            .map(|deadline| deadline.saturating_duration_since(Instant::now()))
+    }
+
+    pub fn publish(
+        &mut self,
+        db: &Database,
+        connection: &Connection,
+    ) -> Result<(), Box<dyn Error + Sync + Send>> {
+        self.deadline = None;
+        for uri in &self.open {
+            let Some(file) = file_path(uri).and_then(|path| db.schema_file(&path)) else {
+                continue;
+            };
+
+            let text = file.text(db);
+            let diagnostics = file_diagnostics(db, file)
+                .iter()
+                .map(|diagnostic| to_lsp(&text, diagnostic))
+                .collect();
+
+            send(connection, uri.clone(), diagnostics)?;
+        }
+
+        Ok(())
+    }
+}
+
+fn send(
+    connection: &Connection,
+    uri: Url,
+    diagnostics: Vec<lsp_types::Diagnostic>,
+) -> Result<(), Box<dyn Error + Sync + Send>> {
+    let params = PublishDiagnosticsParams {
+        uri,
+        diagnostics,
+        version: None,
+    };
+
+    let notification = Notification::new(PublishDiagnostics::METHOD.to_owned(), params);
+    connection
+        .sender
+        .send(Message::Notification(notification))?;
+    Ok(())
+}
+
+pub fn to_lsp(text: &Rope, diagnostic: &Diagnostic) -> lsp_types::Diagnostic {
+    let range = span_to_range(text, &diagnostic.range);
+
+    let severity = match diagnostic.severity {
+        Severity::Error => DiagnosticSeverity::ERROR,
+        Severity::Warning => DiagnosticSeverity::WARNING,
+        Severity::Hint => DiagnosticSeverity::HINT,
+    };
+
+    let tags = match diagnostic.code {
+        Some(Code::UnusedImport) => Some(vec![DiagnosticTag::UNNECESSARY]),
+        _ => None,
+    };
+
+    lsp_types::Diagnostic {
+        range,
+        severity: Some(severity),
+        code: diagnostic
+            .code
+            .map(|code| NumberOrString::String(code.as_str().to_owned())),
+        source: Some("kidl".to_owned()),
+        message: diagnostic.message.clone(),
+        tags,
+        ..Default::default()
+    }
+}
+
+/// Identifies a set of diagnostics by their hash. The salsa revision moves
+/// with every edit anywhere in the workspace, an ID derived from it would
+/// make the client fetch the reports of all files after each keystroke.
+/// The checks behind [`file_diagnostics`] are memoized, so for a file whose
+/// inputs didn't change, computing the diagnostics again only collects the
+/// stored results, and gives the same ID.
+fn result_id(diagnostics: &[Diagnostic]) -> String {
+    let mut hasher = DefaultHasher::new();
+    diagnostics.hash(&mut hasher);
+    format!("{:016x}", hasher.finish())
+}
+
+enum Report {
+    Full(FullDocumentDiagnosticReport),
+    Unchanged(UnchangedDocumentDiagnosticReport),
+}
+
+/// Diagnostics of the file at `uri`, or an unchanged report if they still
+/// have the ID `previous`.
+fn report(db: &Database, uri: &Url, previous: Option<&str>) -> Report {
+    let Some(file) = file_path(uri).and_then(|path| db.schema_file(&path)) else {
+        return Report::Full(FullDocumentDiagnosticReport::default());
+    };
+
+    let diagnostics = file_diagnostics(db, file);
+    let result_id = result_id(&diagnostics);
+    if previous == Some(result_id.as_str()) {
+        return Report::Unchanged(UnchangedDocumentDiagnosticReport { result_id });
+    }
+
+    let text = file.text(db);
+    Report::Full(FullDocumentDiagnosticReport {
+        result_id: Some(result_id),
+        items: diagnostics.iter().map(|d| to_lsp(&text, d)).collect(),
+    })
+}
+
+pub fn document_diagnostics(
+    db: &Database,
+    params: DocumentDiagnosticParams,
+) -> DocumentDiagnosticReportResult {
+    let uri = &params.text_document.uri;
+    let report = match report(db, uri, params.previous_result_id.as_deref()) {
+        Report::Full(report) => {
+            DocumentDiagnosticReport::Full(RelatedFullDocumentDiagnosticReport {
+                related_documents: None,
+                full_document_diagnostic_report: report,
+            })
+        }
+        Report::Unchanged(report) => {
+            DocumentDiagnosticReport::Unchanged(RelatedUnchangedDocumentDiagnosticReport {
+                related_documents: None,
+                unchanged_document_diagnostic_report: report,
+            })
+        }
+    };
+
+    DocumentDiagnosticReportResult::Report(report)
+}
+
+/// Diagnostics of all files of the workspace.
+pub fn workspace_diagnostics(
+    db: &Database,
+    params: WorkspaceDiagnosticParams,
+) -> WorkspaceDiagnosticReportResult {
+    let previous = params
+        .previous_result_ids
+        .into_iter()
+        .map(|previous| (previous.uri, previous.value))
+        .collect::<HashMap<_, _>>();
+
+    let items = db
+        .schema_files()
+        .into_iter()
+        .filter_map(|file| Url::from_file_path(file.path(db)).ok())
+        .map(|uri| {
+            let previous = previous.get(&uri).map(String::as_str);
+            match report(db, &uri, previous) {
+                Report::Full(report) => {
+                    WorkspaceDocumentDiagnosticReport::Full(WorkspaceFullDocumentDiagnosticReport {
+                        uri,
+                        version: None,
+                        full_document_diagnostic_report: report,
+                    })
+                }
+                Report::Unchanged(report) => WorkspaceDocumentDiagnosticReport::Unchanged(
+                    WorkspaceUnchangedDocumentDiagnosticReport {
+                        uri,
+                        version: None,
+                        unchanged_document_diagnostic_report: report,
+                    },
+                ),
+            }
+        })
+        .collect();
+
+    WorkspaceDiagnosticReportResult::Report(WorkspaceDiagnosticReport { items })
+}
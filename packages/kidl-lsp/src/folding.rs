@@ -0,0 +1,145 @@
+use kidl_db::Database;
+use kidl_syn::{
+    ast::{helpers::trimmed_range, SyntaxNode, SyntaxToken, TextSize},
+    kind::{NodeKind, SyntaxKind, TokenKind},
+};
+use lsp_types::{
+    FoldingRange, FoldingRangeKind, FoldingRangeParams, FoldingRangeProviderCapability,
+};
+use ropey::Rope;
+
+use crate::file_path;
+
+pub(crate) fn capabilities() -> FoldingRangeProviderCapability {
+    FoldingRangeProviderCapability::Simple(true)
+}
+
+fn line(text: &Rope, offset: TextSize) -> u32 {
+    text.byte_to_line(usize::from(offset).min(text.len_bytes())) as u32
+}
+
+fn folding_range(start_line: u32, end_line: u32, kind: Option<FoldingRangeKind>) -> FoldingRange {
+    FoldingRange {
+        start_line,
+        start_character: None,
+        end_line,
+        end_character: None,
+        kind,
+        collapsed_text: None,
+    }
+}
+
+/// Struct bodies, runs of `use` items and comments spanning several lines.
+pub fn folding_ranges(db: &Database, params: FoldingRangeParams) -> Option<Vec<FoldingRange>> {
+    let file = db.schema_file(&file_path(&params.text_document.uri)?)?;
+    let text = file.text(db);
+    let root = SyntaxNode::new_root(kidl_db::source::parse(db, file));
+
+    let mut ranges = Vec::new();
+    struct_bodies(&text, &root, &mut ranges);
+    imports(&text, &root, &mut ranges);
+    comments(&text, &root, &mut ranges);
+
+    ranges.sort_by_key(|range| (range.start_line, range.end_line));
+    Some(ranges)
+}
+
+/// From the line of `{` to the line before `}`, so that the closing brace
+/// stays visible.
+fn struct_bodies(text: &Rope, root: &SyntaxNode, ranges: &mut Vec<FoldingRange>) {
+    let structs = root
+        .descendants()
+        .filter(|node| node.kind() == SyntaxKind::Node(NodeKind::Struct));
+
+    for node in structs {
+        let mut tokens = node.children_with_tokens().filter_map(|e| e.into_token());
+        let open = tokens
+            .by_ref()
+            .find(|t| t.kind() == SyntaxKind::Token(TokenKind::CurlyOpen));
+        let close = tokens.find(|t| t.kind() == SyntaxKind::Token(TokenKind::CurlyClose));
+
+        let start_line = match open {
+            Some(open) => line(text, open.text_range().start()),
+            None => continue,
+        };
+        let end_line = match close {
+            Some(close) => line(text, close.text_range().start()).saturating_sub(1),
+            None => line(text, node.text_range().end()),
+        };
+
+        if end_line > start_line {
+            ranges.push(folding_range(start_line, end_line, None));
+        }
+    }
+}
+
+/// `use` items that follow each other without another item in between.
+fn imports(text: &Rope, root: &SyntaxNode, ranges: &mut Vec<FoldingRange>) {
+    let mut run: Option<(u32, u32)> = None;
+    for node in root.children() {
+        if node.kind() != SyntaxKind::Node(NodeKind::Use) {
+            if let Some((start, end)) = run.take() {
+                push_run(ranges, start, end, FoldingRangeKind::Imports);
+            }
+            continue;
+        }
+
+        let range = trimmed_range(&node);
+        let end = line(text, range.end());
+        run = match run {
+            Some((start, _)) => Some((start, end)),
+            None => Some((line(text, range.start()), end)),
+        };
+    }
+
+    if let Some((start, end)) = run {
+        push_run(ranges, start, end, FoldingRangeKind::Imports);
+    }
+}
+
+/// Comments on consecutive lines. Comments after code on their line don't
+/// count, folding them would hide the code.
+fn comments(text: &Rope, root: &SyntaxNode, ranges: &mut Vec<FoldingRange>) {
+    let comments = root
+        .descendants_with_tokens()
+        .filter_map(|e| e.into_token())
+        .filter(|t| t.kind() == SyntaxKind::Token(TokenKind::Comment) && starts_line(t));
+
+    let mut run: Option<(u32, u32)> = None;
+    for comment in comments {
+        let current = line(text, comment.text_range().start());
+        run = match run {
+            Some((start, end)) if current == end + 1 => Some((start, current)),
+            Some((start, end)) => {
+                push_run(ranges, start, end, FoldingRangeKind::Comment);
+                Some((current, current))
+            }
+            None => Some((current, current)),
+        };
+    }
+
+    if let Some((start, end)) = run {
+        push_run(ranges, start, end, FoldingRangeKind::Comment);
+    }
+}
+
+/// Whether only spaces come before `comment` on its line. Comments include
+/// their line break, and spaces can contain line breaks too.
+fn starts_line(comment: &SyntaxToken) -> bool {
+    let mut token = comment.prev_token();
+    while let Some(current) = token {
+        match current.kind() {
+            SyntaxKind::Token(TokenKind::Space) if current.text().contains('\n') => return true,
+            SyntaxKind::Token(TokenKind::Space) => token = current.prev_token(),
+            SyntaxKind::Token(TokenKind::NewLine | TokenKind::Comment) => return true,
+            _ => return false,
+        }
+    }
+    true
+}
+
+fn push_run(ranges: &mut Vec<FoldingRange>, start: u32, end: u32, kind: FoldingRangeKind) {
+    if end > start {
+        ranges.push(folding_range(start, end, Some(kind)));
+    }
+}
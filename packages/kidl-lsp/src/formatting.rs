@@ -0,0 +1,169 @@
+use std::ops::Range;
+
+use kidl_db::{Database, Db};
+use kidl_syn::{
+    ast::{SyntaxNode, TextSize},
+    format::{format_str, FormatConfig},
+    helpers::ByteOffset,
+    kind::{NodeKind, SyntaxKind, TokenKind},
+};
+use lsp_types::{
+    DocumentFormattingParams, DocumentOnTypeFormattingOptions, DocumentOnTypeFormattingParams,
+    DocumentRangeFormattingParams, FormattingOptions, TextEdit, Url,
+};
+use ropey::Rope;
+use similar::{DiffTag, TextDiff};
+
+use crate::{
+    file_path,
+    position::{position_to_byte, span_to_range},
+};
+
+pub(crate) fn on_type_capabilities() -> DocumentOnTypeFormattingOptions {
+    DocumentOnTypeFormattingOptions {
+        first_trigger_character: "}".to_owned(),
+        more_trigger_character: None,
+    }
+}
+
+fn config(db: &Database, options: &FormattingOptions) -> FormatConfig {
+    FormatConfig {
+        indent_width: options.tab_size as usize,
+        use_tabs: !options.insert_spaces,
+        field_separators: db.workspace().parse_options(db).field_separators,
+        ..Default::default()
+    }
+}
+
+/// Replacements of byte ranges of `old` that turn it into `new`, one for
+/// each run of changed lines. Sending only these keeps the cursor and the
+/// undo history of the editor where they were. With `per_line`, runs that
+/// keep the number of lines are split into one replacement for each line.
+fn diff(old: &str, new: &str, per_line: bool) -> Vec<(Range<usize>, String)> {
+    let diff = TextDiff::from_lines(old, new);
+    let (old_lines, new_lines) = (diff.old_slices(), diff.new_slices());
+
+    let mut offsets = vec![0];
+    for line in old_lines {
+        offsets.push(offsets[offsets.len() - 1] + line.len());
+    }
+
+    let mut edits = Vec::new();
+    for op in diff.ops() {
+        let (tag, old, new) = op.as_tag_tuple();
+        if tag == DiffTag::Equal {
+            continue;
+        }
+
+        if per_line && old.len() == new.len() {
+            let lines = old
+                .zip(new)
+                .filter(|&(old, new)| old_lines[old] != new_lines[new]);
+            for (old, new) in lines {
+                edits.push((offsets[old]..offsets[old + 1], new_lines[new].to_owned()));
+            }
+            continue;
+        }
+
+        edits.push((
+            offsets[old.start]..offsets[old.end],
+            new_lines[new].concat(),
+        ));
+    }
+
+    edits
+}
+
+/// The lines `range` touches, from the start of the first one to the end
+/// of the last one.
+fn line_bounds(text: &Rope, range: &Range<usize>) -> Range<usize> {
+    let first = text.byte_to_line(range.start);
+    let last = match range.end > range.start {
+        true => text.byte_to_line(range.end - 1),
+        false => first,
+    };
+    text.line_to_byte(first)..text.line_to_byte(last + 1)
+}
+
+/// Edits that format the document at `uri`. With `within`, only lines it
+/// touches are changed, and changes that can't be split from lines around
+/// them are left out. Documents with syntax errors are left alone.
+fn edits(
+    db: &Database,
+    uri: &Url,
+    options: &FormattingOptions,
+    within: Option<Range<usize>>,
+) -> Option<Vec<TextEdit>> {
+    let file = db.schema_file(&file_path(uri)?)?;
+    let text = file.text(db);
+    let source = text.to_string();
+    let formatted = format_str(&source, &config(db, options)).ok()?;
+
+    let within = within.map(|within| line_bounds(&text, &within));
+    let edits = diff(&source, &formatted, within.is_some())
+        .into_iter()
+        .filter(|(range, _)| match &within {
+            Some(within) => within.start <= range.start && range.end <= within.end,
+            None => true,
+        })
+        .map(|(range, new_text)| TextEdit {
+            range: span_to_range(&text, &(ByteOffset(range.start)..ByteOffset(range.end))),
+            new_text,
+        })
+        .collect();
+
+    Some(edits)
+}
+
+pub fn formatting(db: &Database, params: DocumentFormattingParams) -> Option<Vec<TextEdit>> {
+    edits(db, &params.text_document.uri, &params.options, None)
+}
+
+pub fn range_formatting(
+    db: &Database,
+    params: DocumentRangeFormattingParams,
+) -> Option<Vec<TextEdit>> {
+    let file = db.schema_file(&file_path(&params.text_document.uri)?)?;
+    let text = file.text(db);
+    if params.range.end.line as usize >= text.len_lines() {
+        return None;
+    }
+
+    let range =
+        position_to_byte(&text, params.range.start)..position_to_byte(&text, params.range.end);
+    edits(db, &params.text_document.uri, &params.options, Some(range))
+}
+
+/// Formats the struct closed by the `}` that was just typed.
+pub fn on_type_formatting(
+    db: &Database,
+    params: DocumentOnTypeFormattingParams,
+) -> Option<Vec<TextEdit>> {
+    let position = &params.text_document_position;
+    let file = db.schema_file(&file_path(&position.text_document.uri)?)?;
+    let text = file.text(db);
+    if position.position.line as usize >= text.len_lines() {
+        return None;
+    }
+
+    let offset = position_to_byte(&text, position.position);
+    let root = SyntaxNode::new_root(kidl_db::source::parse(db, file));
+    let token = root
+        .token_at_offset(TextSize::try_from(offset).ok()?)
+        .left_biased()?;
+    if token.kind() != SyntaxKind::Token(TokenKind::CurlyClose) {
+        return None;
+    }
+
+    let node = token
+        .parent_ancestors()
+        .find(|node| node.kind() == SyntaxKind::Node(NodeKind::Struct))?;
+    let range = node.text_range();
+    let range = usize::from(range.start())..usize::from(range.end());
+    edits(
+        db,
+        &position.text_document.uri,
+        &params.options,
+        Some(range),
+    )
+}
@@ -1,6 +1,10 @@
-use std::{ops::Range, path::Path};
+use std::ops::Range;
 
-use kidl_db::Database;
+use kidl_db::{
+    ir::{Name, SchemaFile},
+    resolve::{resolve_type_ref, Resolution},
+    Database,
+};
 use kidl_syn::{
     ast::{
         r#struct::{Struct, StructField},
@@ -17,6 +21,8 @@ use lsp_types::{
 };
 use ropey::Rope;
 
+use crate::file_path;
+
 #[allow(non_camel_case_types)]
 pub enum TokenType {
     NAMESPACE = 0,
@@ -125,7 +131,6 @@ impl<'a> DeltaEncoder<'a> {
         self.buffer.push(token)
     }
 
-    #[allow(unused)]
     fn push_mod(&mut self, token: &SyntaxToken, ty: TokenType, modifier: TokenModifier) {
         let token = self.encode(token, ty, modifier);
         self.buffer.push(token)
@@ -163,11 +168,31 @@ impl<'a> DeltaEncoder<'a> {
     }
 }
 
+/// Semantic information about the highlighted file.
+pub struct Context<'a> {
+    pub db: &'a Database,
+    pub file: SchemaFile,
+}
+
+impl<'a> Context<'a> {
+    fn is_builtin(&self, name: &str) -> bool {
+        let name = Name::new(self.db, name.to_owned());
+        matches!(
+            resolve_type_ref(self.db, self.file, name),
+            Some(Resolution::Builtin(_))
+        )
+    }
+}
+
 pub fn collect_semantic_tokens_from_comment(token: &SyntaxToken, encoder: &mut DeltaEncoder) {
     encoder.push(token, TokenType::COMMENT)
 }
 
-fn collect_semantic_tokens_from_struct_field(arg: &StructField, encoder: &mut DeltaEncoder) {
+fn collect_semantic_tokens_from_struct_field(
+    arg: &StructField,
+    ctx: &Context,
+    encoder: &mut DeltaEncoder,
+) {
     for child in arg.syntax().children_with_tokens() {
         match child {
             NodeOrToken::Token(token) => match token.kind() {
@@ -176,7 +201,7 @@ fn collect_semantic_tokens_from_struct_field(arg: &StructField, encoder: &mut De
             },
             NodeOrToken::Node(node) => match node.kind() {
                 SyntaxKind::Node(NodeKind::Type) => {
-                    collect_semantic_tokens_from_type(&Type::cast(node).unwrap(), encoder)
+                    collect_semantic_tokens_from_type(&Type::cast(node).unwrap(), ctx, encoder)
                 }
                 _ => {}
             },
@@ -184,10 +209,13 @@ fn collect_semantic_tokens_from_struct_field(arg: &StructField, encoder: &mut De
     }
 }
 
-fn collect_semantic_tokens_from_type(ty: &Type, encoder: &mut DeltaEncoder) {
+fn collect_semantic_tokens_from_type(ty: &Type, ctx: &Context, encoder: &mut DeltaEncoder) {
     for child in ty.syntax().children_with_tokens() {
         match child {
             NodeOrToken::Token(token) => match token.kind() {
+                SyntaxKind::Token(TokenKind::Ident) if ctx.is_builtin(token.text()) => {
+                    encoder.push_mod(&token, TokenType::TYPE, TokenModifier::DEFAULT_LIBRARY)
+                }
                 SyntaxKind::Token(TokenKind::Ident) => encoder.push(&token, TokenType::TYPE),
                 _ => {}
             },
@@ -195,7 +223,7 @@ fn collect_semantic_tokens_from_type(ty: &Type, encoder: &mut DeltaEncoder) {
                 SyntaxKind::Node(NodeKind::TypeArguments) => {
                     node.children().for_each(|c| {
                         if let Some(ty) = Type::cast(c) {
-                            collect_semantic_tokens_from_type(&ty, encoder)
+                            collect_semantic_tokens_from_type(&ty, ctx, encoder)
                         }
                     });
                 }
@@ -205,7 +233,7 @@ fn collect_semantic_tokens_from_type(ty: &Type, encoder: &mut DeltaEncoder) {
     }
 }
 
-fn collect_semantic_tokens_from_struct(s: &Struct, encoder: &mut DeltaEncoder) {
+fn collect_semantic_tokens_from_struct(s: &Struct, ctx: &Context, encoder: &mut DeltaEncoder) {
     for child in s.syntax().children_with_tokens() {
         match child {
             NodeOrToken::Token(token) => match token.kind() {
@@ -222,6 +250,7 @@ fn collect_semantic_tokens_from_struct(s: &Struct, encoder: &mut DeltaEncoder) {
                 SyntaxKind::Node(NodeKind::StructField) => {
                     collect_semantic_tokens_from_struct_field(
                         &StructField::cast(node).unwrap(),
+                        ctx,
                         encoder,
                     )
                 }
@@ -231,10 +260,14 @@ fn collect_semantic_tokens_from_struct(s: &Struct, encoder: &mut DeltaEncoder) {
     }
 }
 
-pub fn collect_semantic_tokens_from_schema(node: &Schema, encoder: &mut DeltaEncoder) {
+pub fn collect_semantic_tokens_from_schema(
+    node: &Schema,
+    ctx: &Context,
+    encoder: &mut DeltaEncoder,
+) {
     for decl in node.declarations() {
         match decl {
-            Declaration::Struct(s) => collect_semantic_tokens_from_struct(&s, encoder),
+            Declaration::Struct(s) => collect_semantic_tokens_from_struct(&s, ctx, encoder),
             _ => {}
         }
     }
@@ -244,9 +277,9 @@ pub fn semantic_tokens(
     db: &mut Database,
     params: SemanticTokensParams,
 ) -> Option<SemanticTokensResult> {
-    let path = Path::new(params.text_document.uri.path());
+    let path = file_path(&params.text_document.uri)?;
 
-    let Some(schema_file) = db.schema_file(path) else  {
+    let Some(schema_file) = db.schema_file(&path) else  {
         return None;
     };
 
@@ -259,7 +292,12 @@ pub fn semantic_tokens(
 
     let schema = Schema::cast(schema).unwrap();
 
-    collect_semantic_tokens_from_schema(&schema, &mut delta);
+    let ctx = Context {
+        db,
+        file: schema_file,
+    };
+
+    collect_semantic_tokens_from_schema(&schema, &ctx, &mut delta);
 
     Some(SemanticTokensResult::Tokens(SemanticTokens {
         data: delta.buffer,
@@ -0,0 +1,200 @@
+use kidl_db::{
+    imports::imported_item,
+    ir::{lower_file, Item, SchemaFile, TypeRef},
+    prelude::Builtin,
+    resolve::{resolve_type_ref, Resolution},
+    Database, Db,
+};
+use kidl_syn::ast::{
+    helpers::{doc_comment, token_at_offset, HasName},
+    r#struct::{Struct, StructField},
+    AstNode as _, SyntaxNode,
+};
+use lsp_types::{
+    Hover, HoverContents, HoverParams, HoverProviderCapability, MarkupContent, MarkupKind,
+};
+
+use crate::{
+    navigation::{definition_target, item_at, Cursor, Target},
+    position::span_to_range,
+};
+
+pub(crate) fn capabilities() -> HoverProviderCapability {
+    HoverProviderCapability::Simple(true)
+}
+
+pub fn hover(db: &Database, params: HoverParams) -> Option<Hover> {
+    let cursor = Cursor::at(db, &params.text_document_position_params)?;
+    let value = contents(db, &cursor)?;
+
+    Some(Hover {
+        contents: HoverContents::Markup(MarkupContent {
+            kind: MarkupKind::Markdown,
+            value,
+        }),
+        range: Some(span_to_range(&cursor.file.text(db), &cursor.span())),
+    })
+}
+
+fn contents(db: &Database, cursor: &Cursor) -> Option<String> {
+    if let Some(field) = cursor.token.parent().and_then(StructField::cast) {
+        if field.name().is_some_and(|name| name.0 == cursor.token) {
+            return field_hover(db, cursor, &field);
+        }
+    }
+
+    if let Some(Resolution::Builtin(builtin)) = cursor.type_resolution(db) {
+        return Some(builtin_hover(builtin));
+    }
+
+    if let Some(item) = item_at(db, cursor) {
+        return Some(item_hover(db, item));
+    }
+
+    match definition_target(db, cursor)? {
+        Target::File(file) => Some(format!("Module `{}`", display_path(db, file))),
+        Target::Item(_) | Target::Use(_) => None,
+    }
+}
+
+fn field_hover(db: &Database, cursor: &Cursor, field: &StructField) -> Option<String> {
+    let start = cursor.span().start;
+    let (def, field_def) = lower_file(db, cursor.file)
+        .structs(db)
+        .iter()
+        .find_map(|def| {
+            let field = def
+                .fields(db)
+                .iter()
+                .find(|field| field.name_span(db).start == start)?;
+            Some((*def, *field))
+        })?;
+
+    let optional = field.is_optional().is_some();
+    let signature = format!(
+        "struct {} {{ {}{}: {} }}",
+        def.name(db).text(db),
+        field_def.name(db).text(db),
+        if optional { "?" } else { "" },
+        type_text(db, field_def.ty(db).as_ref())
+    );
+
+    let mut details = vec![match optional {
+        true => "Optional field, it can be left out".to_owned(),
+        false => "Required field".to_owned(),
+    }];
+    if let Some(ty) = field_def.ty(db) {
+        details.push(describe_type(db, cursor.file, ty));
+    }
+    details.push(format!("Declared in `{}`", display_path(db, cursor.file)));
+
+    Some(markdown(&signature, doc_comment(field.syntax()), &details))
+}
+
+fn item_hover(db: &Database, item: Item) -> String {
+    let name = item.name(db).text(db);
+    let signature = match item {
+        Item::Struct(def) => {
+            let fields = def
+                .fields(db)
+                .iter()
+                .map(|field| {
+                    format!(
+                        "    {}{}: {},\n",
+                        field.name(db).text(db),
+                        if field.optional(db) { "?" } else { "" },
+                        type_text(db, field.ty(db).as_ref())
+                    )
+                })
+                .collect::<String>();
+
+            match fields.is_empty() {
+                true => format!("struct {} {{}}", name),
+                false => format!("struct {} {{\n{}}}", name, fields),
+            }
+        }
+        Item::Enum(def) => {
+            let variants = def
+                .variants(db)
+                .iter()
+                .map(|variant| format!("{:?}", variant.name.text(db)))
+                .collect::<Vec<_>>();
+            format!("enum {} {{ {} }}", name, variants.join(", "))
+        }
+        Item::Alias(def) => format!("type {} = {}", name, type_text(db, def.ty(db).as_ref())),
+    };
+
+    let details = [format!("Declared in `{}`", display_path(db, item.file(db)))];
+    markdown(&signature, item_docs(db, item), &details)
+}
+
+fn builtin_hover(builtin: Builtin) -> String {
+    let details = [match builtin.arity() {
+        0 => "Built-in type".to_owned(),
+        1 => "Built-in type with 1 type argument".to_owned(),
+        arity => format!("Built-in type with {} type arguments", arity),
+    }];
+    markdown(builtin.name(), None, &details)
+}
+
+/// Comments above a struct declaration. Other items come from WebIDL
+/// files and have no documentation.
+fn item_docs(db: &Database, item: Item) -> Option<String> {
+    let Item::Struct(def) = item else {
+        return None;
+    };
+
+    let root = SyntaxNode::new_root(kidl_db::source::parse(db, def.file(db)));
+    let name = token_at_offset(&root, def.name_span(db).start)?;
+    let node = name.parent().and_then(Struct::cast)?;
+    doc_comment(node.syntax())
+}
+
+/// What the type of a field in `file` resolves to.
+fn describe_type(db: &Database, file: SchemaFile, ty: &TypeRef) -> String {
+    let name = ty.name.text(db);
+    let item = match resolve_type_ref(db, file, ty.name) {
+        Some(Resolution::Builtin(_)) => return format!("`{}` is a built-in type", name),
+        Some(Resolution::Local(item)) => Some(item),
+        Some(Resolution::Import(item)) => imported_item(db, item),
+        None => None,
+    };
+
+    let Some(item) = item else {
+        return format!("`{}` isn't declared", name);
+    };
+
+    let path = display_path(db, item.file(db));
+    match item {
+        Item::Struct(_) => format!("`{}` is a struct from `{}`", name, path),
+        Item::Enum(_) => format!("`{}` is an enum from `{}`", name, path),
+        Item::Alias(def) => format!(
+            "`{}` is an alias of `{}` from `{}`",
+            name,
+            type_text(db, def.ty(db).as_ref()),
+            path
+        ),
+    }
+}
+
+/// Fields and aliases whose type can't be lowered have no type.
+fn type_text(db: &Database, ty: Option<&TypeRef>) -> String {
+    ty.map_or_else(|| "unknown".to_owned(), |ty| ty.text(db))
+}
+
+fn display_path(db: &Database, file: SchemaFile) -> String {
+    db.workspace().display_path(db, file.path(db))
+}
+
+fn markdown(signature: &str, docs: Option<String>, details: &[String]) -> String {
+    let mut value = format!("```kidl\n{}\n```\n", signature);
+    if let Some(docs) = docs {
+        value.push('\n');
+        value.push_str(&docs);
+        value.push('\n');
+    }
+
+    value.push_str("\n---\n\n");
+    value.push_str(&details.join("  \n"));
+    value
+}
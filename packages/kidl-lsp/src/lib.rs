@@ -1,19 +1,37 @@
-use std::error::Error;
+use std::{error::Error, path::PathBuf};
 
-use kidl_db::Database;
+use kidl_db::{manifest::Project, vendor::VendorCache, Database};
 
 use lsp_server::{
     Connection, ExtractError, Message, Notification, Request, Response, ResponseError,
 };
 
 use lsp_types::{
-    notification::{DidChangeTextDocument, DidOpenTextDocument, Notification as _},
-    request::{Request as _, SemanticTokensFullRequest},
-    InitializeParams, PositionEncodingKind, ServerCapabilities,
+    notification::{
+        DidChangeTextDocument, DidCloseTextDocument, DidOpenTextDocument, Notification as _,
+    },
+    request::{
+        Completion, DocumentDiagnosticRequest, DocumentHighlightRequest, DocumentSymbolRequest,
+        FoldingRangeRequest, Formatting, GotoDeclaration, GotoDefinition, GotoTypeDefinition,
+        HoverRequest, OnTypeFormatting, PrepareRenameRequest, RangeFormatting, References, Rename,
+        Request as _, SelectionRangeRequest, SemanticTokensFullRequest, WorkspaceDiagnosticRequest,
+        WorkspaceSymbolRequest,
+    },
+    InitializeParams, OneOf, PositionEncodingKind, ServerCapabilities, Url,
 };
 
+pub mod completion;
+pub mod diagnostics;
+pub mod folding;
+pub mod formatting;
 pub mod highlight;
+pub mod hover;
+pub mod navigation;
 pub mod position;
+pub mod references;
+pub mod rename;
+pub mod selection;
+pub mod symbols;
 pub mod text;
 
 pub fn start() -> Result<(), Box<dyn Error + Sync + Send>> {
@@ -25,6 +43,22 @@ pub fn start() -> Result<(), Box<dyn Error + Sync + Send>> {
         position_encoding: Some(PositionEncodingKind::UTF16),
         text_document_sync: Some(self::text::capabilities()),
         semantic_tokens_provider: Some(self::highlight::capabilities()),
+        diagnostic_provider: Some(self::diagnostics::capabilities()),
+        definition_provider: Some(self::navigation::definition_capabilities()),
+        declaration_provider: Some(self::navigation::declaration_capabilities()),
+        type_definition_provider: Some(self::navigation::type_definition_capabilities()),
+        references_provider: Some(OneOf::Left(true)),
+        document_highlight_provider: Some(OneOf::Left(true)),
+        rename_provider: Some(OneOf::Right(self::rename::capabilities())),
+        hover_provider: Some(self::hover::capabilities()),
+        completion_provider: Some(self::completion::capabilities()),
+        document_symbol_provider: Some(OneOf::Left(true)),
+        workspace_symbol_provider: Some(OneOf::Left(true)),
+        document_formatting_provider: Some(OneOf::Left(true)),
+        document_range_formatting_provider: Some(OneOf::Left(true)),
+        document_on_type_formatting_provider: Some(self::formatting::on_type_capabilities()),
+        folding_range_provider: Some(self::folding::capabilities()),
+        selection_range_provider: Some(self::selection::capabilities()),
         ..Default::default()
     })
     .unwrap();
@@ -42,12 +76,67 @@ pub fn main_loop(
     connection: Connection,
     params: serde_json::Value,
 ) -> Result<(), Box<dyn Error + Sync + Send>> {
-    let _params: InitializeParams = serde_json::from_value(params).unwrap();
+    let params: InitializeParams = serde_json::from_value(params).unwrap();
     eprintln!("starting example main loop");
 
-    let mut db = Database::default();
+    let root = params
+        .root_uri
+        .as_ref()
+        .and_then(file_path)
+        .unwrap_or_default();
+
+    let project = Project::discover(&root).unwrap_or_else(|e| {
+        tracing::warn!("failed to load the manifest: {}", e);
+        Project {
+            root,
+            manifest: Default::default(),
+        }
+    });
+
+    let mut db = match VendorCache::open(&project.root) {
+        Ok(cache) => Database::new(project.root.clone()).with_import_resolver(cache),
+        Err(e) => {
+            tracing::warn!("failed to open the vendor cache: {}", e);
+            Database::new(project.root.clone())
+        }
+    };
+
+    // Without a workspace folder there is nothing to search
+    if params.root_uri.is_some() {
+        match db.load_project(&project) {
+            Ok((_, errors)) => {
+                for e in errors {
+                    tracing::warn!("failed to load a file: {}", e);
+                }
+            }
+            Err(e) => tracing::warn!("failed to load the project: {}", e),
+        }
+    }
+
+    // Clients that pull diagnostics don't need them pushed
+    let pull_diagnostics = params
+        .capabilities
+        .text_document
+        .as_ref()
+        .is_some_and(|capabilities| capabilities.diagnostic.is_some());
+    let mut diagnostics = self::diagnostics::Publisher::new(!pull_diagnostics);
+
+    loop {
+        let msg = match diagnostics.timeout() {
+            Some(timeout) => match connection.receiver.recv_timeout(timeout) {
+                Ok(msg) => msg,
+                Err(e) if e.is_timeout() => {
+                    diagnostics.publish(&db, &connection)?;
+                    continue;
+                }
+                Err(_) => break,
+            },
+            None => match connection.receiver.recv() {
+                Ok(msg) => msg,
+                Err(_) => break,
+            },
+        };
 
-    for msg in &connection.receiver {
         match msg {
             Message::Request(req) => {
                 if connection.handle_shutdown(&req)? {
@@ -64,6 +153,74 @@ pub fn main_loop(
                             self::highlight::semantic_tokens(&mut db, params)
                         })
                     }
+                    DocumentDiagnosticRequest::METHOD => {
+                        answer::<DocumentDiagnosticRequest>(req, |params| {
+                            self::diagnostics::document_diagnostics(&db, params)
+                        })
+                    }
+                    WorkspaceDiagnosticRequest::METHOD => {
+                        answer::<WorkspaceDiagnosticRequest>(req, |params| {
+                            self::diagnostics::workspace_diagnostics(&db, params)
+                        })
+                    }
+                    GotoDefinition::METHOD => answer::<GotoDefinition>(req, |params| {
+                        self::navigation::definition(&db, params)
+                    }),
+                    GotoDeclaration::METHOD => answer::<GotoDeclaration>(req, |params| {
+                        self::navigation::declaration(&db, params)
+                    }),
+                    GotoTypeDefinition::METHOD => answer::<GotoTypeDefinition>(req, |params| {
+                        self::navigation::type_definition(&db, params)
+                    }),
+                    HoverRequest::METHOD => {
+                        answer::<HoverRequest>(req, |params| self::hover::hover(&db, params))
+                    }
+                    Completion::METHOD => answer::<Completion>(req, |params| {
+                        self::completion::completion(&db, params)
+                    }),
+                    DocumentSymbolRequest::METHOD => {
+                        answer::<DocumentSymbolRequest>(req, |params| {
+                            self::symbols::document_symbols(&db, params)
+                        })
+                    }
+                    WorkspaceSymbolRequest::METHOD => {
+                        answer::<WorkspaceSymbolRequest>(req, |params| {
+                            self::symbols::workspace_symbols(&db, params)
+                        })
+                    }
+                    Formatting::METHOD => answer::<Formatting>(req, |params| {
+                        self::formatting::formatting(&db, params)
+                    }),
+                    RangeFormatting::METHOD => answer::<RangeFormatting>(req, |params| {
+                        self::formatting::range_formatting(&db, params)
+                    }),
+                    OnTypeFormatting::METHOD => answer::<OnTypeFormatting>(req, |params| {
+                        self::formatting::on_type_formatting(&db, params)
+                    }),
+                    FoldingRangeRequest::METHOD => answer::<FoldingRangeRequest>(req, |params| {
+                        self::folding::folding_ranges(&db, params)
+                    }),
+                    SelectionRangeRequest::METHOD => {
+                        answer::<SelectionRangeRequest>(req, |params| {
+                            self::selection::selection_ranges(&db, params)
+                        })
+                    }
+                    References::METHOD => answer::<References>(req, |params| {
+                        self::references::references(&db, params)
+                    }),
+                    DocumentHighlightRequest::METHOD => {
+                        answer::<DocumentHighlightRequest>(req, |params| {
+                            self::references::document_highlights(&db, params)
+                        })
+                    }
+                    PrepareRenameRequest::METHOD => {
+                        try_answer::<PrepareRenameRequest>(req, |params| {
+                            self::rename::prepare_rename(&db, params)
+                        })
+                    }
+                    Rename::METHOD => {
+                        try_answer::<Rename>(req, |params| self::rename::rename(&db, params))
+                    }
                     _ => Err(ResponseError {
                         code: 0,
                         message: String::from("Unknown request"),
@@ -99,10 +256,16 @@ pub fn main_loop(
                 DidOpenTextDocument::METHOD => {
                     let params = cast_notification::<DidOpenTextDocument>(not).unwrap();
                     crate::text::open(&mut db, &params);
+                    diagnostics.open(params.text_document.uri);
                 }
                 DidChangeTextDocument::METHOD => {
                     let params = cast_notification::<DidChangeTextDocument>(not).unwrap();
                     crate::text::edit(&mut db, &params.text_document.uri, params.content_changes);
+                    diagnostics.changed();
+                }
+                DidCloseTextDocument::METHOD => {
+                    let params = cast_notification::<DidCloseTextDocument>(not).unwrap();
+                    diagnostics.close(&connection, params.text_document.uri)?;
                 }
                 _ => {
                     eprintln!("got notification: {:?}", not.method);
@@ -113,6 +276,12 @@ pub fn main_loop(
     Ok(())
 }
 
+/// The path of the file at `uri`, with the percent-encoding of the URI
+/// decoded. Documents are keyed by this path in the database.
+pub fn file_path(uri: &Url) -> Option<PathBuf> {
+    uri.to_file_path().ok()
+}
+
 pub fn to_response_error(err: ExtractError<Request>) -> ResponseError {
     ResponseError {
         code: 0,
@@ -134,6 +303,19 @@ where
         .map_err(to_response_error)
 }
 
+/// Like [`answer`], for requests that can fail.
+pub fn try_answer<R>(
+    req: Request,
+    func: impl FnOnce(R::Params) -> Result<R::Result, ResponseError>,
+) -> Result<serde_json::Value, ResponseError>
+where
+    R: lsp_types::request::Request,
+    R::Params: serde::de::DeserializeOwned,
+{
+    let (_, params) = req.extract(R::METHOD).map_err(to_response_error)?;
+    func(params).map(|result| serde_json::to_value(&result).unwrap())
+}
+
 fn cast_notification<N>(req: Notification) -> Result<N::Params, ExtractError<Notification>>
 where
     N: lsp_types::notification::Notification,
@@ -0,0 +1,213 @@
+use kidl_db::{
+    imports::{imported_item, resolve_import},
+    ir::{lower_file, Item, Name, SchemaFile, Span, UseDef},
+    resolve::{resolve_type_ref, Resolution},
+    Database,
+};
+use kidl_syn::{
+    ast::{
+        helpers::{token_at_offset, HasName},
+        r#struct::StructField,
+        AstNode as _, SyntaxNode, SyntaxToken,
+    },
+    helpers::ByteOffset,
+    kind::{NodeKind, SyntaxKind, TokenKind},
+};
+use lsp_types::{
+    DeclarationCapability, GotoDefinitionParams, GotoDefinitionResponse, Location, OneOf,
+    TextDocumentPositionParams, TypeDefinitionProviderCapability, Url,
+};
+
+use crate::{
+    file_path,
+    position::{position_to_byte, span_to_range},
+};
+
+pub(crate) fn definition_capabilities() -> OneOf<bool, lsp_types::DefinitionOptions> {
+    OneOf::Left(true)
+}
+
+pub(crate) fn declaration_capabilities() -> DeclarationCapability {
+    DeclarationCapability::Simple(true)
+}
+
+pub(crate) fn type_definition_capabilities() -> TypeDefinitionProviderCapability {
+    TypeDefinitionProviderCapability::Simple(true)
+}
+
+/// A name or path segment under the cursor.
+pub struct Cursor {
+    pub file: SchemaFile,
+    pub token: SyntaxToken,
+}
+
+impl Cursor {
+    pub fn at(db: &Database, position: &TextDocumentPositionParams) -> Option<Cursor> {
+        let file = db.schema_file(&file_path(&position.text_document.uri)?)?;
+        let text = file.text(db);
+        if position.position.line as usize >= text.len_lines() {
+            return None;
+        }
+
+        let offset = ByteOffset(position_to_byte(&text, position.position));
+        let root = SyntaxNode::new_root(kidl_db::source::parse(db, file));
+        let token = token_at_offset(&root, offset)?;
+
+        match token.kind() {
+            SyntaxKind::Token(TokenKind::Ident | TokenKind::String) => Some(Cursor { file, token }),
+            _ => None,
+        }
+    }
+
+    pub fn span(&self) -> Span {
+        let range = self.token.text_range();
+        ByteOffset(range.start().into())..ByteOffset(range.end().into())
+    }
+
+    fn parent_kind(&self) -> Option<NodeKind> {
+        match self.token.parent()?.kind() {
+            SyntaxKind::Node(kind) => Some(kind),
+            SyntaxKind::Token(_) => None,
+        }
+    }
+
+    /// The `use` item and the index of the path segment under the cursor.
+    pub fn use_segment(&self, db: &Database) -> Option<(UseDef, usize)> {
+        if self.parent_kind()? != NodeKind::Path {
+            return None;
+        }
+
+        let start = self.span().start;
+        lower_file(db, self.file).uses(db).iter().find_map(|item| {
+            let index = item
+                .path(db)
+                .iter()
+                .position(|segment| segment.span().start == start)?;
+            Some((*item, index))
+        })
+    }
+
+    /// How the type name under the cursor resolves.
+    pub fn type_resolution(&self, db: &Database) -> Option<Resolution> {
+        if self.parent_kind()? != NodeKind::Type {
+            return None;
+        }
+
+        let name = Name::new(db, self.token.text().to_owned());
+        resolve_type_ref(db, self.file, name)
+    }
+}
+
+/// What a name refers to.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Target {
+    Item(Item),
+    Use(UseDef),
+    File(SchemaFile),
+}
+
+impl Target {
+    pub fn location(self, db: &Database) -> Option<Location> {
+        let (file, span) = match self {
+            Target::Item(item) => (item.file(db), item.name_span(db)),
+            Target::Use(item) => (item.file(db), item.span(db)),
+            Target::File(file) => (file, ByteOffset(0)..ByteOffset(0)),
+        };
+
+        location(db, file, &span)
+    }
+}
+
+pub fn location(db: &Database, file: SchemaFile, span: &Span) -> Option<Location> {
+    Some(Location {
+        uri: Url::from_file_path(file.path(db)).ok()?,
+        range: span_to_range(&file.text(db), span),
+    })
+}
+
+/// The item declared or referred to under the cursor.
+pub fn item_at(db: &Database, cursor: &Cursor) -> Option<Item> {
+    let start = cursor.span().start;
+    let declared = lower_file(db, cursor.file)
+        .items(db)
+        .into_iter()
+        .find(|item| item.name_span(db).start == start);
+
+    match declared {
+        Some(item) => Some(item),
+        None => match definition_target(db, cursor)? {
+            Target::Item(item) => Some(item),
+            Target::Use(_) | Target::File(_) => None,
+        },
+    }
+}
+
+/// The item a type name or the last segment of a `use` path refers to, or
+/// the file a module segment of a `use` path points to.
+pub fn definition_target(db: &Database, cursor: &Cursor) -> Option<Target> {
+    if let Some((item, index)) = cursor.use_segment(db) {
+        if index + 1 == item.path(db).len() {
+            return imported_item(db, item).map(Target::Item);
+        }
+
+        return resolve_import(db, item).map(Target::File);
+    }
+
+    match cursor.type_resolution(db)? {
+        Resolution::Local(item) => Some(Target::Item(item)),
+        Resolution::Import(item) => match imported_item(db, item) {
+            Some(imported) => Some(Target::Item(imported)),
+            None => Some(Target::Use(item)),
+        },
+        Resolution::Builtin(_) => None,
+    }
+}
+
+/// Like [`definition_target`], but an imported type name leads to the
+/// `use` item that brings it into scope.
+fn declaration_target(db: &Database, cursor: &Cursor) -> Option<Target> {
+    match cursor.type_resolution(db) {
+        Some(Resolution::Import(item)) => Some(Target::Use(item)),
+        _ => definition_target(db, cursor),
+    }
+}
+
+/// On a field name, the definition of the field's type, elsewhere the
+/// definition of the name.
+fn type_definition_target(db: &Database, cursor: &Cursor) -> Option<Target> {
+    let field = cursor.token.parent().and_then(StructField::cast);
+    let Some(ty) = field.and_then(|field| field.ty()) else {
+        return definition_target(db, cursor);
+    };
+
+    let cursor = Cursor {
+        file: cursor.file,
+        token: ty.name()?.0,
+    };
+    definition_target(db, &cursor)
+}
+
+fn goto(
+    db: &Database,
+    params: GotoDefinitionParams,
+    target: fn(&Database, &Cursor) -> Option<Target>,
+) -> Option<GotoDefinitionResponse> {
+    let cursor = Cursor::at(db, &params.text_document_position_params)?;
+    let location = target(db, &cursor)?.location(db)?;
+    Some(GotoDefinitionResponse::Scalar(location))
+}
+
+pub fn definition(db: &Database, params: GotoDefinitionParams) -> Option<GotoDefinitionResponse> {
+    goto(db, params, definition_target)
+}
+
+pub fn declaration(db: &Database, params: GotoDefinitionParams) -> Option<GotoDefinitionResponse> {
+    goto(db, params, declaration_target)
+}
+
+pub fn type_definition(
+    db: &Database,
+    params: GotoDefinitionParams,
+) -> Option<GotoDefinitionResponse> {
+    goto(db, params, type_definition_target)
+}
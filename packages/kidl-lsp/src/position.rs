@@ -1,3 +1,6 @@
+use std::ops::Range;
+
+use kidl_syn::helpers::ByteOffset;
 use lsp_types::Position;
 use ropey::Rope;
 
@@ -25,3 +28,12 @@ pub fn byte_to_position(buffer: &Rope, byte: usize) -> Position {
         character: character_utf16 as u32,
     }
 }
+
+/// LSP range of a byte range, clamped to the end of the buffer.
+pub fn span_to_range(buffer: &Rope, span: &Range<ByteOffset>) -> lsp_types::Range {
+    let len = buffer.len_bytes();
+    lsp_types::Range {
+        start: byte_to_position(buffer, span.start.0.min(len)),
+        end: byte_to_position(buffer, span.end.0.min(len)),
+    }
+}
@@ -0,0 +1,53 @@
+use kidl_db::{references::reference_index, Database, Db};
+use lsp_types::{
+    DocumentHighlight, DocumentHighlightKind, DocumentHighlightParams, Location, ReferenceParams,
+};
+
+use crate::{
+    navigation::{item_at, location, Cursor},
+    position::span_to_range,
+};
+
+pub fn references(db: &Database, params: ReferenceParams) -> Option<Vec<Location>> {
+    let cursor = Cursor::at(db, &params.text_document_position)?;
+    let item = item_at(db, &cursor)?;
+
+    let declaration = params
+        .context
+        .include_declaration
+        .then(|| location(db, item.file(db), &item.name_span(db)))
+        .flatten();
+
+    let references = reference_index(db, db.workspace())
+        .references(item)
+        .iter()
+        .filter_map(|reference| location(db, reference.file, &reference.span));
+
+    Some(declaration.into_iter().chain(references).collect())
+}
+
+/// The name under the cursor everywhere it appears in the same file.
+pub fn document_highlights(
+    db: &Database,
+    params: DocumentHighlightParams,
+) -> Option<Vec<DocumentHighlight>> {
+    let cursor = Cursor::at(db, &params.text_document_position_params)?;
+    let item = item_at(db, &cursor)?;
+    let text = cursor.file.text(db);
+
+    let declaration = (item.file(db) == cursor.file).then(|| DocumentHighlight {
+        range: span_to_range(&text, &item.name_span(db)),
+        kind: Some(DocumentHighlightKind::TEXT),
+    });
+
+    let references = reference_index(db, db.workspace())
+        .references(item)
+        .iter()
+        .filter(|reference| reference.file == cursor.file)
+        .map(|reference| DocumentHighlight {
+            range: span_to_range(&text, &reference.span),
+            kind: Some(DocumentHighlightKind::READ),
+        });
+
+    Some(declaration.into_iter().chain(references).collect())
+}
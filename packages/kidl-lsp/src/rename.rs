@@ -0,0 +1,168 @@
+use std::collections::HashMap;
+
+use kidl_db::{
+    ir::{lower_file, FieldDef, Item, Name, SchemaFile, Span, StructDef},
+    prelude::Builtin,
+    references::reference_index,
+    resolve::{file_scope, Resolution},
+    vendor::VENDOR_DIR,
+    Database, Db,
+};
+use kidl_syn::parser::is_identifier;
+use lsp_server::{ErrorCode, ResponseError};
+use lsp_types::{
+    PrepareRenameResponse, RenameOptions, RenameParams, TextDocumentPositionParams, TextEdit, Url,
+    WorkspaceEdit,
+};
+
+use crate::{
+    navigation::{item_at, Cursor},
+    position::span_to_range,
+};
+
+pub(crate) fn capabilities() -> RenameOptions {
+    RenameOptions {
+        prepare_provider: Some(true),
+        work_done_progress_options: Default::default(),
+    }
+}
+
+/// A declaration that can be renamed.
+enum Renamable {
+    Item(Item),
+    Field(StructDef, FieldDef),
+}
+
+fn error(message: String) -> ResponseError {
+    ResponseError {
+        code: ErrorCode::InvalidParams as i32,
+        message,
+        data: None,
+    }
+}
+
+fn renamable_at(db: &Database, cursor: &Cursor) -> Result<Renamable, ResponseError> {
+    if let Some(Resolution::Builtin(builtin)) = cursor.type_resolution(db) {
+        return Err(error(format!("`{}` is a built-in type", builtin)));
+    }
+
+    let start = cursor.span().start;
+    for def in lower_file(db, cursor.file).structs(db) {
+        if let Some(field) = def
+            .fields(db)
+            .iter()
+            .find(|field| field.name_span(db).start == start)
+        {
+            return Ok(Renamable::Field(*def, *field));
+        }
+    }
+
+    let item = item_at(db, cursor).ok_or_else(|| error("Nothing to rename here".to_owned()))?;
+    let vendor_dir = db.workspace().root(db).join(VENDOR_DIR);
+    if item.file(db).path(db).starts_with(vendor_dir) {
+        return Err(error(format!(
+            "`{}` is declared in a vendored file",
+            item.name(db).text(db)
+        )));
+    }
+
+    Ok(Renamable::Item(item))
+}
+
+pub fn prepare_rename(
+    db: &Database,
+    params: TextDocumentPositionParams,
+) -> Result<Option<PrepareRenameResponse>, ResponseError> {
+    let Some(cursor) = Cursor::at(db, &params) else {
+        return Ok(None);
+    };
+
+    renamable_at(db, &cursor)?;
+    Ok(Some(PrepareRenameResponse::RangeWithPlaceholder {
+        range: span_to_range(&cursor.file.text(db), &cursor.span()),
+        placeholder: cursor.token.text().to_owned(),
+    }))
+}
+
+pub fn rename(db: &Database, params: RenameParams) -> Result<Option<WorkspaceEdit>, ResponseError> {
+    let Some(cursor) = Cursor::at(db, &params.text_document_position) else {
+        return Ok(None);
+    };
+
+    let new_name = params.new_name.as_str();
+    if !is_identifier(new_name) {
+        return Err(error(format!("`{}` isn't a valid name", new_name)));
+    }
+
+    let mut edits = Edits::default();
+    match renamable_at(db, &cursor)? {
+        Renamable::Field(def, field) => {
+            let collides = def
+                .fields(db)
+                .iter()
+                .any(|other| other != &field && other.name(db).text(db) == new_name);
+            if collides {
+                return Err(error(format!(
+                    "`{}` already has a field named `{}`",
+                    def.name(db).text(db),
+                    new_name
+                )));
+            }
+
+            edits.push(db, cursor.file, &field.name_span(db), new_name);
+        }
+        Renamable::Item(item) => {
+            if Builtin::from_name(new_name).is_some() {
+                return Err(error(format!("`{}` is a built-in type", new_name)));
+            }
+
+            let references = reference_index(db, db.workspace()).references(item);
+            let mut files = vec![item.file(db)];
+            for reference in references {
+                if !files.contains(&reference.file) {
+                    files.push(reference.file);
+                }
+            }
+
+            // The name is declared or imported in all these files
+            let name = Name::new(db, new_name.to_owned());
+            for file in files {
+                if item.name(db) != name && file_scope(db, file).get(name).is_some() {
+                    return Err(error(format!(
+                        "`{}` is already declared in `{}`",
+                        new_name,
+                        db.workspace().display_path(db, file.path(db))
+                    )));
+                }
+            }
+
+            edits.push(db, item.file(db), &item.name_span(db), new_name);
+            for reference in references {
+                edits.push(db, reference.file, &reference.span, new_name);
+            }
+        }
+    }
+
+    Ok(Some(WorkspaceEdit {
+        changes: Some(edits.changes),
+        ..Default::default()
+    }))
+}
+
+#[derive(Default)]
+struct Edits {
+    changes: HashMap<Url, Vec<TextEdit>>,
+}
+
+impl Edits {
+    fn push(&mut self, db: &Database, file: SchemaFile, span: &Span, new_text: &str) {
+        let Ok(uri) = Url::from_file_path(file.path(db)) else {
+            return;
+        };
+
+        self.changes.entry(uri).or_default().push(TextEdit {
+            range: span_to_range(&file.text(db), span),
+            new_text: new_text.to_owned(),
+        });
+    }
+}
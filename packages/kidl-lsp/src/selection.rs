@@ -0,0 +1,84 @@
+use kidl_db::Database;
+use kidl_syn::{
+    ast::{
+        helpers::{token_at_offset, trimmed_range},
+        SyntaxNode,
+    },
+    helpers::ByteOffset,
+};
+use lsp_types::{
+    Position, Range, SelectionRange, SelectionRangeParams, SelectionRangeProviderCapability,
+};
+use ropey::Rope;
+use rowan::TextRange;
+
+use crate::{
+    file_path,
+    position::{position_to_byte, span_to_range},
+};
+
+pub(crate) fn capabilities() -> SelectionRangeProviderCapability {
+    SelectionRangeProviderCapability::Simple(true)
+}
+
+fn to_range(text: &Rope, range: TextRange) -> Range {
+    let span = ByteOffset(range.start().into())..ByteOffset(range.end().into());
+    span_to_range(text, &span)
+}
+
+/// From the token under each position outwards through the nodes around
+/// it, e.g. a name, its type, the field, the struct and the whole file.
+pub fn selection_ranges(
+    db: &Database,
+    params: SelectionRangeParams,
+) -> Option<Vec<SelectionRange>> {
+    let file = db.schema_file(&file_path(&params.text_document.uri)?)?;
+    let text = file.text(db);
+    let root = SyntaxNode::new_root(kidl_db::source::parse(db, file));
+
+    let ranges = params
+        .positions
+        .into_iter()
+        .map(|position| selection_range(&text, &root, position))
+        .collect();
+    Some(ranges)
+}
+
+fn selection_range(text: &Rope, root: &SyntaxNode, position: Position) -> SelectionRange {
+    let empty = SelectionRange {
+        range: Range::new(position, position),
+        parent: None,
+    };
+    if position.line as usize >= text.len_lines() {
+        return empty;
+    }
+
+    let offset = ByteOffset(position_to_byte(text, position));
+    let Some(token) = token_at_offset(root, offset) else {
+        return empty;
+    };
+
+    // Innermost first, without repeating a range
+    let mut ranges = vec![token.text_range()];
+    for node in token.parent_ancestors() {
+        let range = match node.parent() {
+            Some(_) => trimmed_range(&node),
+            None => node.text_range(),
+        };
+
+        if !ranges.contains(&range) && range.contains_range(ranges[ranges.len() - 1]) {
+            ranges.push(range);
+        }
+    }
+
+    ranges
+        .into_iter()
+        .rev()
+        .fold(None, |parent, range| {
+            Some(SelectionRange {
+                range: to_range(text, range),
+                parent: parent.map(Box::new),
+            })
+        })
+        .unwrap_or(empty)
+}
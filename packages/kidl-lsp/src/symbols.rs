@@ -0,0 +1,191 @@
+use kidl_db::{
+    ir::{lower_file, FieldDef, Item, Span, TypeRef},
+    Database, Db,
+};
+use lsp_types::{
+    DocumentSymbol, DocumentSymbolParams, DocumentSymbolResponse, SymbolInformation, SymbolKind,
+    WorkspaceSymbolParams, WorkspaceSymbolResponse,
+};
+use ropey::Rope;
+
+use crate::{file_path, navigation::location, position::span_to_range};
+
+fn symbol_kind(item: Item) -> SymbolKind {
+    match item {
+        Item::Struct(_) => SymbolKind::STRUCT,
+        Item::Enum(_) => SymbolKind::ENUM,
+        // There is no kind for type aliases, editors commonly show them as
+        // type parameters
+        Item::Alias(_) => SymbolKind::TYPE_PARAMETER,
+    }
+}
+
+fn type_text(db: &Database, ty: Option<&TypeRef>) -> Option<String> {
+    ty.map(|ty| ty.text(db))
+}
+
+#[allow(deprecated)]
+fn symbol(
+    text: &Rope,
+    name: String,
+    kind: SymbolKind,
+    detail: Option<String>,
+    span: &Span,
+    name_span: &Span,
+    children: Option<Vec<DocumentSymbol>>,
+) -> DocumentSymbol {
+    DocumentSymbol {
+        name,
+        detail,
+        kind,
+        tags: None,
+        deprecated: None,
+        range: span_to_range(text, span),
+        selection_range: span_to_range(text, name_span),
+        children,
+    }
+}
+
+fn field_symbol(db: &Database, text: &Rope, field: FieldDef) -> DocumentSymbol {
+    let detail = type_text(db, field.ty(db).as_ref()).map(|ty| match field.optional(db) {
+        true => format!("{} (optional)", ty),
+        false => ty,
+    });
+
+    symbol(
+        text,
+        field.name(db).text(db).clone(),
+        SymbolKind::FIELD,
+        detail,
+        &field.span(db),
+        &field.name_span(db),
+        None,
+    )
+}
+
+/// Items of the document in source order, with the fields of structs and
+/// the variants of enums as children.
+pub fn document_symbols(
+    db: &Database,
+    params: DocumentSymbolParams,
+) -> Option<DocumentSymbolResponse> {
+    let file = db.schema_file(&file_path(&params.text_document.uri)?)?;
+    let text = file.text(db);
+
+    let mut items = lower_file(db, file).items(db);
+    items.sort_by_key(|item| item.span(db).start);
+
+    let symbols = items
+        .into_iter()
+        .map(|item| {
+            let (detail, children) = match item {
+                Item::Struct(def) => {
+                    let fields = def
+                        .fields(db)
+                        .iter()
+                        .map(|field| field_symbol(db, &text, *field))
+                        .collect();
+                    (None, Some(fields))
+                }
+                Item::Enum(def) => {
+                    let variants = def
+                        .variants(db)
+                        .iter()
+                        .map(|variant| {
+                            symbol(
+                                &text,
+                                variant.name.text(db).clone(),
+                                SymbolKind::ENUM_MEMBER,
+                                None,
+                                &variant.span,
+                                &variant.span,
+                                None,
+                            )
+                        })
+                        .collect();
+                    (None, Some(variants))
+                }
+                Item::Alias(def) => (type_text(db, def.ty(db).as_ref()), None),
+            };
+
+            symbol(
+                &text,
+                item.name(db).text(db).clone(),
+                symbol_kind(item),
+                detail,
+                &item.span(db),
+                &item.name_span(db),
+                children,
+            )
+        })
+        .collect();
+
+    Some(DocumentSymbolResponse::Nested(symbols))
+}
+
+/// How well `name` matches `query`, lower is better. The characters of the
+/// query have to appear in the name in order, ignoring case. Matches that
+/// start earlier and have fewer gaps rank higher.
+fn fuzzy_score(query: &str, name: &str) -> Option<usize> {
+    let mut score = 0;
+    let mut last = None;
+    let mut chars = name.char_indices();
+
+    for wanted in query.chars() {
+        let (index, found) = chars
+            .by_ref()
+            .find(|(_, c)| c.to_lowercase().eq(wanted.to_lowercase()))?;
+
+        score += match last {
+            None => index,
+            Some(last) => index - last - 1,
+        };
+        if found != wanted {
+            score += 1;
+        }
+        last = Some(index + found.len_utf8() - 1);
+    }
+
+    Some(score)
+}
+
+/// Declarations of all files whose name matches the query, best matches
+/// first.
+#[allow(deprecated)]
+pub fn workspace_symbols(
+    db: &Database,
+    params: WorkspaceSymbolParams,
+) -> Option<WorkspaceSymbolResponse> {
+    let mut matches = Vec::new();
+    for file in db.schema_files() {
+        for item in lower_file(db, file).items(db) {
+            let name = item.name(db).text(db);
+            if let Some(score) = fuzzy_score(&params.query, name) {
+                matches.push((score, item));
+            }
+        }
+    }
+
+    matches.sort_by(|(a_score, a), (b_score, b)| {
+        a_score
+            .cmp(b_score)
+            .then_with(|| a.name(db).text(db).cmp(b.name(db).text(db)))
+    });
+
+    let symbols = matches
+        .into_iter()
+        .filter_map(|(_, item)| {
+            let file = item.file(db);
+            Some(SymbolInformation {
+                name: item.name(db).text(db).clone(),
+                kind: symbol_kind(item),
+                tags: None,
+                deprecated: None,
+                location: location(db, file, &item.name_span(db))?,
+                container_name: Some(db.workspace().display_path(db, file.path(db))),
+            })
+        })
+        .collect();
+
+    Some(WorkspaceSymbolResponse::Flat(symbols))
+}
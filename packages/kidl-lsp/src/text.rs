@@ -1,5 +1,3 @@
-use std::path::Path;
-
 use kidl_db::Database;
 use lsp_types::{
     DidOpenTextDocumentParams, SaveOptions, TextDocumentContentChangeEvent,
@@ -8,7 +6,7 @@ use lsp_types::{
 };
 use ropey::Rope;
 
-use crate::position::position_to_char;
+use crate::{file_path, position::position_to_char};
 
 pub(crate) fn capabilities() -> TextDocumentSyncCapability {
     TextDocumentSyncCapability::Options(TextDocumentSyncOptions {
@@ -21,11 +19,14 @@ pub(crate) fn capabilities() -> TextDocumentSyncCapability {
     })
 }
 
-pub(crate) fn edit(db: &mut Database, uri: &Url, edits: Vec<TextDocumentContentChangeEvent>) {
-    let path = Path::new(uri.path());
+pub fn edit(db: &mut Database, uri: &Url, edits: Vec<TextDocumentContentChangeEvent>) {
+    let Some(path) = file_path(uri) else {
+        tracing::warn!(uri = %uri, "Ignoring changes to a document that isn't a file");
+        return;
+    };
 
     tracing::info!(path = ?path, "Applying changes to the document");
-    match db.schema_file(path) {
+    match db.schema_file(&path) {
         Some(schema_file) => {
             let mut buffer = schema_file.text(db);
             for edit in edits {
@@ -49,19 +50,24 @@ pub(crate) fn edit(db: &mut Database, uri: &Url, edits: Vec<TextDocumentContentC
             assert_eq!(edits.len(), 1);
             let change = edits.into_iter().next().unwrap();
             assert!(change.range.is_none());
-            db.push_file(path.to_owned(), change.text);
+            db.push_file(path, change.text);
         }
     }
 }
 
-pub(crate) fn open(db: &mut Database, params: &DidOpenTextDocumentParams) {
-    let path = Path::new(params.text_document.uri.path());
-    let source = std::fs::read_to_string(path).unwrap();
-    if let Some(schema_file) = db.schema_file(path) {
+pub fn open(db: &mut Database, params: &DidOpenTextDocumentParams) {
+    let uri = &params.text_document.uri;
+    let Some(path) = file_path(uri) else {
+        tracing::warn!(uri = %uri, "Ignoring a document that isn't a file");
+        return;
+    };
+
+    let source = std::fs::read_to_string(&path).unwrap();
+    if let Some(schema_file) = db.schema_file(&path) {
         tracing::info!(path = ?path, "Re-Open existing document");
         schema_file.set_text(db).to(Rope::from(source));
     } else {
         tracing::info!(path = ?path, "Open new document");
-        db.push_file(path.to_owned(), source);
+        db.push_file(path, source);
     }
 }
@@ -0,0 +1,94 @@
+//! Helpers shared by the language server tests.
+
+#![allow(dead_code)]
+
+use std::path::{Path, PathBuf};
+
+use kidl_db::Database;
+use kidl_lsp::position::{byte_to_position, position_to_byte};
+use lsp_types::{
+    Location, Position, Range, TextDocumentIdentifier, TextDocumentPositionParams, TextEdit, Url,
+};
+use ropey::Rope;
+
+pub const ROOT: &str = "/project";
+
+pub fn path(relative: &str) -> PathBuf {
+    Path::new(ROOT).join(relative)
+}
+
+pub fn uri(relative: &str) -> Url {
+    Url::from_file_path(path(relative)).unwrap()
+}
+
+/// A database for a project in [`ROOT`] with `files`, given as paths
+/// relative to the root and their text.
+pub fn database(files: &[(&str, &str)]) -> Database {
+    let mut db = Database::new(PathBuf::from(ROOT));
+    for (relative, text) in files {
+        db.push_file(path(relative), text.to_string());
+    }
+    db
+}
+
+/// Offset of the first occurrence of `needle` in the file at `relative`.
+fn find(db: &Database, relative: &str, needle: &str) -> (Rope, usize) {
+    let text = db.schema_file(&path(relative)).unwrap().text(db);
+    let offset = text
+        .to_string()
+        .find(needle)
+        .unwrap_or_else(|| panic!("no `{}` in {}", needle, relative));
+    (text, offset)
+}
+
+/// Position of the first occurrence of `needle` in the file at `relative`.
+pub fn position(db: &Database, relative: &str, needle: &str) -> TextDocumentPositionParams {
+    let (text, offset) = find(db, relative, needle);
+    TextDocumentPositionParams {
+        text_document: TextDocumentIdentifier { uri: uri(relative) },
+        position: byte_to_position(&text, offset),
+    }
+}
+
+/// Location of the first occurrence of `needle` in the file at `relative`.
+pub fn location(db: &Database, relative: &str, needle: &str) -> Location {
+    let (text, offset) = find(db, relative, needle);
+    Location {
+        uri: uri(relative),
+        range: Range {
+            start: byte_to_position(&text, offset),
+            end: byte_to_position(&text, offset + needle.len()),
+        },
+    }
+}
+
+/// An empty directory for the test `name` to write to.
+pub fn temp_dir(name: &str) -> PathBuf {
+    let dir = std::env::temp_dir().join(format!("kidl-lsp-{}-{}", name, std::process::id()));
+    let _ = std::fs::remove_dir_all(&dir);
+    std::fs::create_dir_all(&dir).unwrap();
+    dir
+}
+
+/// `text` with `edits` applied, they must not overlap.
+pub fn apply(text: &str, edits: &[TextEdit]) -> String {
+    let rope = Rope::from_str(text);
+    let offset = |position: Position| position_to_byte(&rope, position);
+
+    let mut edits = edits
+        .iter()
+        .map(|edit| {
+            (
+                offset(edit.range.start)..offset(edit.range.end),
+                &edit.new_text,
+            )
+        })
+        .collect::<Vec<_>>();
+    edits.sort_by_key(|(range, _)| std::cmp::Reverse(range.start));
+
+    let mut text = text.to_owned();
+    for (range, new_text) in edits {
+        text.replace_range(range, new_text);
+    }
+    text
+}
@@ -0,0 +1,209 @@
+mod common;
+
+use common::{database, temp_dir, uri};
+use kidl_db::Database;
+use kidl_lsp::{
+    diagnostics::{document_diagnostics, Publisher},
+    text,
+};
+use lsp_server::{Connection, Message};
+use lsp_types::{
+    notification::{Notification as _, PublishDiagnostics},
+    DidOpenTextDocumentParams, DocumentDiagnosticParams, DocumentDiagnosticReport,
+    DocumentDiagnosticReportResult, PublishDiagnosticsParams, TextDocumentContentChangeEvent,
+    TextDocumentIdentifier, TextDocumentItem, Url,
+};
+
+const BROKEN: &str = "struct A {\n    a: Missing,\n}\n";
+const FIXED: &str = "struct A {\n    a: string,\n}\n";
+
+/// Diagnostics of the document at `uri`, `previous` is the ID of the last
+/// report the client got.
+fn pull(db: &Database, uri: &Url, previous: Option<&str>) -> DocumentDiagnosticReport {
+    let params = DocumentDiagnosticParams {
+        text_document: TextDocumentIdentifier { uri: uri.clone() },
+        identifier: None,
+        previous_result_id: previous.map(str::to_owned),
+        work_done_progress_params: Default::default(),
+        partial_result_params: Default::default(),
+    };
+
+    match document_diagnostics(db, params) {
+        DocumentDiagnosticReportResult::Report(report) => report,
+        result => panic!("{:?}", result),
+    }
+}
+
+/// Messages of a full report.
+fn messages(report: &DocumentDiagnosticReport) -> Vec<String> {
+    match report {
+        DocumentDiagnosticReport::Full(report) => report
+            .full_document_diagnostic_report
+            .items
+            .iter()
+            .map(|diagnostic| diagnostic.message.clone())
+            .collect(),
+        report => panic!("{:?}", report),
+    }
+}
+
+fn result_id(report: &DocumentDiagnosticReport) -> String {
+    match report {
+        DocumentDiagnosticReport::Full(report) => report
+            .full_document_diagnostic_report
+            .result_id
+            .clone()
+            .unwrap(),
+        DocumentDiagnosticReport::Unchanged(report) => report
+            .unchanged_document_diagnostic_report
+            .result_id
+            .clone(),
+    }
+}
+
+/// Diagnostics the server pushed to `client` so far, with the document
+/// they belong to.
+fn published(client: &Connection) -> Vec<(Url, Vec<String>)> {
+    client
+        .receiver
+        .try_iter()
+        .map(|message| match message {
+            Message::Notification(notification)
+                if notification.method == PublishDiagnostics::METHOD =>
+            {
+                let params: PublishDiagnosticsParams =
+                    serde_json::from_value(notification.params).unwrap();
+                let messages = params
+                    .diagnostics
+                    .into_iter()
+                    .map(|diagnostic| diagnostic.message)
+                    .collect();
+                (params.uri, messages)
+            }
+            message => panic!("{:?}", message),
+        })
+        .collect()
+}
+
+fn change(text: &str) -> Vec<TextDocumentContentChangeEvent> {
+    vec![TextDocumentContentChangeEvent {
+        range: None,
+        range_length: None,
+        text: text.to_owned(),
+    }]
+}
+
+#[test]
+fn percent_encoded_uris() {
+    let dir = temp_dir("percent-encoded-uris");
+    let path = dir.join("my schemas/a.kidl");
+    std::fs::create_dir_all(path.parent().unwrap()).unwrap();
+    std::fs::write(&path, BROKEN).unwrap();
+
+    let uri = Url::from_file_path(&path).unwrap();
+    assert!(uri.as_str().ends_with("/my%20schemas/a.kidl"), "{}", uri);
+
+    let mut db = Database::new(dir.clone());
+    text::open(
+        &mut db,
+        &DidOpenTextDocumentParams {
+            text_document: TextDocumentItem {
+                uri: uri.clone(),
+                language_id: "kidl".to_owned(),
+                version: 0,
+                text: BROKEN.to_owned(),
+            },
+        },
+    );
+    assert_eq!(
+        messages(&pull(&db, &uri, None)),
+        ["Unresolved type `Missing`"]
+    );
+
+    // The edit reaches the same file as the diagnostics
+    text::edit(&mut db, &uri, change(FIXED));
+    assert_eq!(db.schema_files(), [db.schema_file(&path).unwrap()]);
+    assert!(messages(&pull(&db, &uri, None)).is_empty());
+}
+
+#[test]
+fn unchanged_reports() {
+    let mut db = database(&[("main.kidl", BROKEN), ("other.kidl", "struct B {}\n")]);
+    let main = uri("main.kidl");
+
+    let first = pull(&db, &main, None);
+    assert_eq!(messages(&first), ["Unresolved type `Missing`"]);
+    let previous = result_id(&first);
+
+    let second = pull(&db, &main, Some(&previous));
+    assert!(
+        matches!(second, DocumentDiagnosticReport::Unchanged(_)),
+        "{:?}",
+        second
+    );
+    assert_eq!(result_id(&second), previous);
+
+    // Edits elsewhere don't change the report
+    text::edit(
+        &mut db,
+        &uri("other.kidl"),
+        change("struct B {\n    b: string,\n}\n"),
+    );
+    let report = pull(&db, &main, Some(&previous));
+    assert!(matches!(report, DocumentDiagnosticReport::Unchanged(_)));
+
+    text::edit(&mut db, &main, change(FIXED));
+    let report = pull(&db, &main, Some(&previous));
+    assert!(messages(&report).is_empty());
+    assert_ne!(result_id(&report), previous);
+}
+
+#[test]
+fn publish_until_closed() {
+    let mut db = database(&[("main.kidl", BROKEN)]);
+    let main = uri("main.kidl");
+    let (server, client) = Connection::memory();
+    let mut publisher = Publisher::new(true);
+    assert_eq!(publisher.timeout(), None);
+
+    // Published once the debounce deadline passes
+    publisher.open(main.clone());
+    assert!(publisher.timeout().is_some());
+    assert!(published(&client).is_empty());
+
+    publisher.publish(&db, &server).unwrap();
+    assert_eq!(publisher.timeout(), None);
+    assert_eq!(
+        published(&client),
+        [(main.clone(), vec!["Unresolved type `Missing`".to_owned()])]
+    );
+
+    text::edit(&mut db, &main, change("struct A {\n    a: Other,\n}\n"));
+    publisher.changed();
+    assert!(publisher.timeout().is_some());
+    publisher.publish(&db, &server).unwrap();
+    assert_eq!(
+        published(&client),
+        [(main.clone(), vec!["Unresolved type `Other`".to_owned()])]
+    );
+
+    publisher.close(&server, main.clone()).unwrap();
+    assert_eq!(published(&client), [(main, Vec::new())]);
+
+    // Closed documents are left alone
+    publisher.changed();
+    publisher.publish(&db, &server).unwrap();
+    assert!(published(&client).is_empty());
+}
+
+#[test]
+fn nothing_is_pushed_to_pulling_clients() {
+    let main = uri("main.kidl");
+    let (server, client) = Connection::memory();
+    let mut publisher = Publisher::new(false);
+
+    publisher.open(main.clone());
+    assert_eq!(publisher.timeout(), None);
+    publisher.close(&server, main).unwrap();
+    assert!(published(&client).is_empty());
+}
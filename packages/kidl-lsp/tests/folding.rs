@@ -0,0 +1,28 @@
+mod common;
+
+use common::{database, uri};
+use kidl_lsp::folding::folding_ranges;
+use lsp_types::{FoldingRangeKind, FoldingRangeParams, TextDocumentIdentifier};
+
+#[test]
+fn trailing_comments_dont_fold() {
+    let source = "// a\n// b\nstruct A {\n    a: B, // trailing\n    // c\n}\n\nstruct B {}\n";
+    let db = database(&[("main.kidl", source)]);
+    let params = FoldingRangeParams {
+        text_document: TextDocumentIdentifier {
+            uri: uri("main.kidl"),
+        },
+        work_done_progress_params: Default::default(),
+        partial_result_params: Default::default(),
+    };
+
+    let ranges = folding_ranges(&db, params)
+        .unwrap()
+        .into_iter()
+        .map(|range| (range.start_line, range.end_line, range.kind))
+        .collect::<Vec<_>>();
+    assert_eq!(
+        ranges,
+        [(0, 1, Some(FoldingRangeKind::Comment)), (2, 4, None)]
+    );
+}
@@ -0,0 +1,131 @@
+mod common;
+
+use common::{apply, database, uri};
+use kidl_lsp::formatting::{formatting, on_type_formatting, range_formatting};
+use kidl_syn::format::{format_str, FormatConfig};
+use lsp_types::{
+    DocumentFormattingParams, DocumentOnTypeFormattingParams, DocumentRangeFormattingParams,
+    FormattingOptions, Position, Range, TextDocumentIdentifier, TextDocumentPositionParams,
+};
+
+const SOURCE: &str = "struct A {\n  a:   string,\n}\n\nstruct B {\n  b:   string,\n}\n";
+
+fn options(insert_spaces: bool) -> FormattingOptions {
+    FormattingOptions {
+        tab_size: 4,
+        insert_spaces,
+        ..Default::default()
+    }
+}
+
+fn document() -> TextDocumentIdentifier {
+    TextDocumentIdentifier {
+        uri: uri("main.kidl"),
+    }
+}
+
+#[test]
+fn edits_reproduce_the_formatted_text() {
+    let db = database(&[("main.kidl", SOURCE)]);
+    let params = DocumentFormattingParams {
+        text_document: document(),
+        options: options(true),
+        work_done_progress_params: Default::default(),
+    };
+
+    let edits = formatting(&db, params).unwrap();
+    // One edit for each changed line
+    assert_eq!(edits.len(), 2);
+    assert_eq!(
+        apply(SOURCE, &edits),
+        format_str(SOURCE, &FormatConfig::default()).unwrap()
+    );
+}
+
+#[test]
+fn indents_with_tabs() {
+    let db = database(&[("main.kidl", SOURCE)]);
+    let params = DocumentFormattingParams {
+        text_document: document(),
+        options: options(false),
+        work_done_progress_params: Default::default(),
+    };
+
+    let edits = formatting(&db, params).unwrap();
+    assert_eq!(
+        apply(SOURCE, &edits),
+        "struct A {\n\ta: string,\n}\n\nstruct B {\n\tb: string,\n}\n"
+    );
+}
+
+#[test]
+fn range_formatting_keeps_other_lines() {
+    let db = database(&[("main.kidl", SOURCE)]);
+    let params = DocumentRangeFormattingParams {
+        text_document: document(),
+        range: Range {
+            start: Position::new(5, 0),
+            end: Position::new(5, 3),
+        },
+        options: options(true),
+        work_done_progress_params: Default::default(),
+    };
+
+    let edits = range_formatting(&db, params).unwrap();
+    assert_eq!(
+        apply(SOURCE, &edits),
+        "struct A {\n  a:   string,\n}\n\nstruct B {\n    b: string,\n}\n"
+    );
+}
+
+#[test]
+fn range_formatting_leaves_neighbouring_lines() {
+    // Both fields are changed in one run of lines
+    let source = "struct A {\n  a:   string,\n  b:   string,\n}\n";
+    let db = database(&[("main.kidl", source)]);
+    let params = DocumentRangeFormattingParams {
+        text_document: document(),
+        range: Range {
+            start: Position::new(1, 0),
+            end: Position::new(1, 2),
+        },
+        options: options(true),
+        work_done_progress_params: Default::default(),
+    };
+
+    let edits = range_formatting(&db, params).unwrap();
+    assert_eq!(
+        apply(source, &edits),
+        "struct A {\n    a: string,\n  b:   string,\n}\n"
+    );
+}
+
+#[test]
+fn on_type_formatting_formats_the_closed_struct() {
+    let db = database(&[("main.kidl", SOURCE)]);
+    let params = DocumentOnTypeFormattingParams {
+        text_document_position: TextDocumentPositionParams {
+            text_document: document(),
+            position: Position::new(2, 1),
+        },
+        ch: "}".to_owned(),
+        options: options(true),
+    };
+
+    let edits = on_type_formatting(&db, params).unwrap();
+    assert_eq!(
+        apply(SOURCE, &edits),
+        "struct A {\n    a: string,\n}\n\nstruct B {\n  b:   string,\n}\n"
+    );
+
+    // Not after a closing brace
+    let params = DocumentOnTypeFormattingParams {
+        text_document_position: TextDocumentPositionParams {
+            text_document: document(),
+            position: Position::new(1, 4),
+        },
+        ch: "}".to_owned(),
+        options: options(true),
+    };
+    assert_eq!(on_type_formatting(&db, params), None);
+}
@@ -0,0 +1,84 @@
+mod common;
+
+use common::{database, location, position};
+use kidl_db::Database;
+use kidl_lsp::hover::hover;
+use lsp_types::{HoverContents, HoverParams, Range};
+
+const TAG: &str = "// Labels posts\nstruct Tag {\n    name: string,\n}\n";
+const MAIN: &str = "use a::Tag;\n\n// A blog post\n// and its labels\nstruct Post {\n    // The main label\n    tag?: Tag,\n    tags: List<Tag>,\n}\n";
+
+/// The markdown shown over the first `needle` in the file at `relative`,
+/// and the range it applies to.
+fn hover_at(db: &Database, relative: &str, needle: &str) -> Option<(String, Range)> {
+    let params = HoverParams {
+        text_document_position_params: position(db, relative, needle),
+        work_done_progress_params: Default::default(),
+    };
+
+    let hover = hover(db, params)?;
+    match hover.contents {
+        HoverContents::Markup(markup) => Some((markup.value, hover.range.unwrap())),
+        contents => panic!("{:?}", contents),
+    }
+}
+
+#[test]
+fn struct_names() {
+    let db = database(&[("a.kidl", TAG), ("main.kidl", MAIN)]);
+
+    assert_eq!(
+        hover_at(&db, "main.kidl", "Post"),
+        Some((
+            "```kidl\nstruct Post {\n    tag?: Tag,\n    tags: List<Tag>,\n}\n```\n\nA blog post\nand its labels\n\n---\n\nDeclared in `main.kidl`".to_owned(),
+            location(&db, "main.kidl", "Post").range,
+        ))
+    );
+}
+
+#[test]
+fn fields() {
+    let db = database(&[("a.kidl", TAG), ("main.kidl", MAIN)]);
+
+    assert_eq!(
+        hover_at(&db, "main.kidl", "tag?"),
+        Some((
+            "```kidl\nstruct Post { tag?: Tag }\n```\n\nThe main label\n\n---\n\nOptional field, it can be left out  \n`Tag` is a struct from `a.kidl`  \nDeclared in `main.kidl`".to_owned(),
+            location(&db, "main.kidl", "tag").range,
+        ))
+    );
+    assert_eq!(
+        hover_at(&db, "main.kidl", "tags").unwrap().0,
+        "```kidl\nstruct Post { tags: List<Tag> }\n```\n\n---\n\nRequired field  \n`List` is a built-in type  \nDeclared in `main.kidl`"
+    );
+}
+
+#[test]
+fn imported_types() {
+    let db = database(&[("a.kidl", TAG), ("main.kidl", MAIN)]);
+
+    // The declaration in the other file, over the name in this one
+    let mut range = location(&db, "main.kidl", "Tag,").range;
+    range.end.character -= 1;
+    assert_eq!(
+        hover_at(&db, "main.kidl", "Tag,"),
+        Some((
+            "```kidl\nstruct Tag {\n    name: string,\n}\n```\n\nLabels posts\n\n---\n\nDeclared in `a.kidl`".to_owned(),
+            range,
+        ))
+    );
+}
+
+#[test]
+fn builtins() {
+    let db = database(&[("a.kidl", TAG), ("main.kidl", MAIN)]);
+
+    assert_eq!(
+        hover_at(&db, "main.kidl", "List").unwrap().0,
+        "```kidl\nList\n```\n\n---\n\nBuilt-in type with 1 type argument"
+    );
+    assert_eq!(
+        hover_at(&db, "a.kidl", "string").unwrap().0,
+        "```kidl\nstring\n```\n\n---\n\nBuilt-in type"
+    );
+}
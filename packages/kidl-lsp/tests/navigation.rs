@@ -0,0 +1,95 @@
+mod common;
+
+use common::{database, location, position, uri};
+use kidl_db::Database;
+use kidl_lsp::navigation::{declaration, definition, type_definition};
+use lsp_types::{
+    GotoDefinitionParams, GotoDefinitionResponse, Location, Position, Range,
+    TextDocumentPositionParams,
+};
+
+const TAG: &str = "struct Tag {}\n";
+const MAIN: &str = "use a::Tag;\n\nstruct Local {}\n\nstruct Post {\n    tag: Tag,\n    local: Local,\n    name: string,\n}\n";
+
+type Goto = fn(&Database, GotoDefinitionParams) -> Option<GotoDefinitionResponse>;
+
+fn goto(db: &Database, request: Goto, position: TextDocumentPositionParams) -> Option<Location> {
+    let params = GotoDefinitionParams {
+        text_document_position_params: position,
+        work_done_progress_params: Default::default(),
+        partial_result_params: Default::default(),
+    };
+
+    match request(db, params)? {
+        GotoDefinitionResponse::Scalar(location) => Some(location),
+        response => panic!("{:?}", response),
+    }
+}
+
+#[test]
+fn field_types() {
+    let db = database(&[("a.kidl", TAG), ("main.kidl", MAIN)]);
+
+    assert_eq!(
+        goto(&db, definition, position(&db, "main.kidl", "Local,")),
+        Some(location(&db, "main.kidl", "Local"))
+    );
+    // The declaration of a local type is its definition
+    assert_eq!(
+        goto(&db, declaration, position(&db, "main.kidl", "Local,")),
+        Some(location(&db, "main.kidl", "Local"))
+    );
+}
+
+#[test]
+fn across_files() {
+    let db = database(&[("a.kidl", TAG), ("main.kidl", MAIN)]);
+    let tag = Some(location(&db, "a.kidl", "Tag"));
+
+    assert_eq!(
+        goto(&db, definition, position(&db, "main.kidl", "Tag,")),
+        tag
+    );
+    // The `use` item brings the name into scope
+    assert_eq!(
+        goto(&db, declaration, position(&db, "main.kidl", "Tag,")),
+        Some(location(&db, "main.kidl", "use a::Tag;"))
+    );
+    // From the field name to its type
+    assert_eq!(
+        goto(&db, type_definition, position(&db, "main.kidl", "tag:")),
+        tag
+    );
+}
+
+#[test]
+fn use_path_segments() {
+    let db = database(&[("a.kidl", TAG), ("main.kidl", MAIN)]);
+
+    // A module segment opens the file
+    assert_eq!(
+        goto(&db, definition, position(&db, "main.kidl", "a::")),
+        Some(Location {
+            uri: uri("a.kidl"),
+            range: Range::new(Position::new(0, 0), Position::new(0, 0)),
+        })
+    );
+    assert_eq!(
+        goto(&db, definition, position(&db, "main.kidl", "Tag;")),
+        Some(location(&db, "a.kidl", "Tag"))
+    );
+}
+
+#[test]
+fn builtins_have_no_definition() {
+    let db = database(&[("a.kidl", TAG), ("main.kidl", MAIN)]);
+
+    assert_eq!(
+        goto(&db, definition, position(&db, "main.kidl", "string")),
+        None
+    );
+    assert_eq!(
+        goto(&db, type_definition, position(&db, "main.kidl", "name:")),
+        None
+    );
+}
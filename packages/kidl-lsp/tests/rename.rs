@@ -0,0 +1,88 @@
+mod common;
+
+use common::{apply, database, position, uri};
+use kidl_db::Database;
+use kidl_lsp::rename::{prepare_rename, rename};
+use lsp_types::{RenameParams, WorkspaceEdit};
+
+const TAG: &str = "struct Tag {}\n";
+const MAIN: &str = "use a::Tag;\n\nstruct Post {\n    tag: Tag,\n    tags: List<Tag>,\n}\n";
+
+fn rename_at(
+    db: &Database,
+    relative: &str,
+    needle: &str,
+    new_name: &str,
+) -> Result<WorkspaceEdit, String> {
+    let params = RenameParams {
+        text_document_position: position(db, relative, needle),
+        new_name: new_name.to_owned(),
+        work_done_progress_params: Default::default(),
+    };
+
+    match rename(db, params) {
+        Ok(edit) => Ok(edit.expect("nothing to rename")),
+        Err(error) => Err(error.message),
+    }
+}
+
+#[test]
+fn renames_imports_in_other_files() {
+    let db = database(&[("a.kidl", TAG), ("main.kidl", MAIN)]);
+
+    let edit = rename_at(&db, "a.kidl", "Tag", "Label").unwrap();
+    let changes = edit.changes.unwrap();
+    assert_eq!(changes.len(), 2);
+    assert_eq!(apply(TAG, &changes[&uri("a.kidl")]), "struct Label {}\n");
+    assert_eq!(
+        apply(MAIN, &changes[&uri("main.kidl")]),
+        "use a::Label;\n\nstruct Post {\n    tag: Label,\n    tags: List<Label>,\n}\n"
+    );
+
+    // The same from a reference
+    let from_reference = rename_at(&db, "main.kidl", "Tag>", "Label").unwrap();
+    assert_eq!(
+        from_reference,
+        WorkspaceEdit {
+            changes: Some(changes),
+            ..Default::default()
+        }
+    );
+}
+
+#[test]
+fn names_in_importing_files_collide() {
+    let main = format!("{}\nstruct Label {{}}\n", MAIN);
+    let db = database(&[("a.kidl", TAG), ("main.kidl", &main)]);
+
+    assert_eq!(
+        rename_at(&db, "a.kidl", "Tag", "Label"),
+        Err("`Label` is already declared in `main.kidl`".to_owned())
+    );
+    assert!(rename_at(&db, "a.kidl", "Tag", "Other").is_ok());
+}
+
+#[test]
+fn builtins_and_vendored_files_are_refused() {
+    let db = database(&[
+        ("a.kidl", TAG),
+        ("main.kidl", MAIN),
+        (".kidl/vendor/shared.kidl", "struct Shared {}\n"),
+    ]);
+
+    assert_eq!(
+        rename_at(&db, "main.kidl", "List", "Vec"),
+        Err("`List` is a built-in type".to_owned())
+    );
+    assert!(prepare_rename(&db, position(&db, "main.kidl", "List")).is_err());
+
+    assert_eq!(
+        rename_at(&db, "a.kidl", "Tag", "u32"),
+        Err("`u32` is a built-in type".to_owned())
+    );
+
+    assert_eq!(
+        rename_at(&db, ".kidl/vendor/shared.kidl", "Shared", "Common"),
+        Err("`Shared` is declared in a vendored file".to_owned())
+    );
+}
@@ -0,0 +1,90 @@
+mod common;
+
+use common::{database, uri};
+use kidl_lsp::symbols::{document_symbols, workspace_symbols};
+use lsp_types::{
+    DocumentSymbolParams, DocumentSymbolResponse, Position, Range, SymbolKind,
+    TextDocumentIdentifier, WorkspaceSymbolParams, WorkspaceSymbolResponse,
+};
+
+fn range(line: u32, start: u32, end: u32) -> Range {
+    Range {
+        start: Position::new(line, start),
+        end: Position::new(line, end),
+    }
+}
+
+#[test]
+fn enum_variants_and_aliases() {
+    let db = database(&[(
+        "dom.webidl",
+        "enum Phase { \"none\", \"capturing\" };\ntypedef DOMString Key;\n",
+    )]);
+    let params = DocumentSymbolParams {
+        text_document: TextDocumentIdentifier {
+            uri: uri("dom.webidl"),
+        },
+        work_done_progress_params: Default::default(),
+        partial_result_params: Default::default(),
+    };
+
+    let Some(DocumentSymbolResponse::Nested(symbols)) = document_symbols(&db, params) else {
+        panic!("no nested symbols");
+    };
+    let [phase, key] = symbols.as_slice() else {
+        panic!("{:?}", symbols);
+    };
+
+    assert_eq!(
+        (phase.name.as_str(), phase.kind),
+        ("Phase", SymbolKind::ENUM)
+    );
+    let variants = phase
+        .children
+        .iter()
+        .flatten()
+        .map(|variant| (variant.name.as_str(), variant.kind, variant.selection_range))
+        .collect::<Vec<_>>();
+    assert_eq!(
+        variants,
+        [
+            ("none", SymbolKind::ENUM_MEMBER, range(0, 13, 19)),
+            ("capturing", SymbolKind::ENUM_MEMBER, range(0, 21, 32)),
+        ]
+    );
+
+    assert_eq!(
+        (key.name.as_str(), key.kind),
+        ("Key", SymbolKind::TYPE_PARAMETER)
+    );
+    assert_eq!(key.detail.as_deref(), Some("string"));
+}
+
+#[test]
+fn aliases_are_not_structs() {
+    let db = database(&[
+        ("dom.webidl", "typedef DOMString Key;\n"),
+        ("main.kidl", "struct Keys {}\n"),
+    ]);
+    let params = WorkspaceSymbolParams {
+        query: "Key".to_owned(),
+        work_done_progress_params: Default::default(),
+        partial_result_params: Default::default(),
+    };
+
+    let Some(WorkspaceSymbolResponse::Flat(symbols)) = workspace_symbols(&db, params) else {
+        panic!("no flat symbols");
+    };
+    let mut kinds = symbols
+        .iter()
+        .map(|symbol| (symbol.name.as_str(), symbol.kind))
+        .collect::<Vec<_>>();
+    kinds.sort_by_key(|(name, _)| *name);
+    assert_eq!(
+        kinds,
+        [
+            ("Key", SymbolKind::TYPE_PARAMETER),
+            ("Keys", SymbolKind::STRUCT),
+        ]
+    );
+}
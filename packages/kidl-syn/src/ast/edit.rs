@@ -0,0 +1,273 @@
+//! In-place editing of mutable syntax trees.
+//!
+//! Obtain a mutable tree with `clone_for_update()` on a parsed node and
+//! build new pieces with [`super::make`]. All edits keep the trivia and
+//! comments around the changed nodes untouched.
+
+use rowan::ast::AstNode;
+
+use crate::kind::{SyntaxKind, TokenKind};
+
+use super::{
+    helpers::first_token_of_kind, ident::Ident, make, r#struct::Struct, r#struct::StructField,
+    r#use::Use, schema::Schema, SyntaxElement, SyntaxNode, SyntaxToken,
+};
+
+fn is_trivia(element: &SyntaxElement) -> bool {
+    matches!(
+        element.kind(),
+        SyntaxKind::Token(TokenKind::Space | TokenKind::NewLine | TokenKind::Comment)
+    )
+}
+
+/// Whitespace that does not break the line.
+fn is_inline_space(element: &SyntaxElement) -> bool {
+    element.kind() == SyntaxKind::Token(TokenKind::Space)
+        && !element.as_token().unwrap().text().contains('\n')
+}
+
+/// Newlines, and comments which always run to the end of the line.
+fn ends_line(element: &SyntaxElement) -> bool {
+    match element.kind() {
+        SyntaxKind::Token(TokenKind::NewLine | TokenKind::Comment) => true,
+        SyntaxKind::Token(TokenKind::Space) => element.as_token().unwrap().text().contains('\n'),
+        _ => false,
+    }
+}
+
+fn insert_at(parent: &SyntaxNode, index: usize, elements: Vec<SyntaxElement>) {
+    parent.splice_children(index..index, elements)
+}
+
+/// Indentation of `node` if it starts its own line.
+fn line_indent(node: &SyntaxNode) -> Option<String> {
+    let prev = node.prev_sibling_or_token()?;
+    if is_inline_space(&prev) {
+        let before = prev.prev_sibling_or_token()?;
+        return ends_line(&before).then(|| prev.as_token().unwrap().text().to_owned());
+    }
+
+    match prev.kind() {
+        SyntaxKind::Token(TokenKind::Space) => {
+            let text = prev.as_token().unwrap().text();
+            Some(text[text.rfind('\n').unwrap() + 1..].to_owned())
+        }
+        _ if ends_line(&prev) => Some(String::new()),
+        _ => None,
+    }
+}
+
+/// Indentation of the fields of other structs in the file, four spaces if
+/// there are none.
+fn field_indent(node: &SyntaxNode) -> String {
+    let root = node.ancestors().last().unwrap();
+    root.descendants()
+        .filter_map(StructField::cast)
+        .find_map(|field| line_indent(field.syntax()).filter(|indent| !indent.is_empty()))
+        .unwrap_or_else(|| "    ".to_owned())
+}
+
+fn token_after(element: &SyntaxElement, predicate: impl Fn(&SyntaxElement) -> bool) -> usize {
+    let mut index = element.index() + 1;
+    let mut next = element.next_sibling_or_token();
+    while let Some(element) = next {
+        if !predicate(&element) {
+            break;
+        }
+        index += 1;
+        next = element.next_sibling_or_token();
+    }
+    index
+}
+
+impl Struct {
+    /// Appends `field` after the last field, following the indentation
+    /// and trailing comma style of the existing fields. In an empty struct
+    /// the field is indented like the fields of other structs.
+    pub fn add_field(&self, field: StructField) {
+        let node = self.syntax();
+        let Some(curly_open) = first_token_of_kind(node, TokenKind::CurlyOpen) else {
+            return;
+        };
+
+        let Some(last) = self.fields().last() else {
+            let start = curly_open.index() + 1;
+            let whitespace = node
+                .children_with_tokens()
+                .skip(start)
+                .take_while(|e| {
+                    is_inline_space(e) || e.kind() == SyntaxKind::Token(TokenKind::NewLine)
+                })
+                .count();
+
+            node.splice_children(
+                start..start + whitespace,
+                vec![
+                    make::tokens::newline().into(),
+                    make::tokens::space(&field_indent(node)).into(),
+                    field.syntax().clone().into(),
+                    make::tokens::newline().into(),
+                ],
+            );
+            field.add_separator();
+            return;
+        };
+
+        let trailing_comma = last.separator().is_some();
+        last.add_separator();
+        let anchor: SyntaxElement = last.separator().unwrap().into();
+
+        let first = self.fields().next().unwrap();
+        let mut elements = Vec::new();
+        match line_indent(first.syntax()) {
+            Some(indent) => {
+                // Keep a trailing comment of the last field on its line
+                let index = token_after(&anchor, is_inline_space);
+                let comment = node
+                    .children_with_tokens()
+                    .nth(index)
+                    .filter(|e| e.kind() == SyntaxKind::Token(TokenKind::Comment));
+
+                let index = match comment {
+                    Some(_) => {
+                        if !indent.is_empty() {
+                            elements.push(make::tokens::space(&indent).into());
+                        }
+                        elements.push(field.syntax().clone().into());
+                        elements.push(make::tokens::newline().into());
+                        index + 1
+                    }
+                    None => {
+                        elements.push(make::tokens::newline().into());
+                        if !indent.is_empty() {
+                            elements.push(make::tokens::space(&indent).into());
+                        }
+                        elements.push(field.syntax().clone().into());
+                        anchor.index() + 1
+                    }
+                };
+
+                insert_at(node, index, elements);
+            }
+            None => {
+                elements.push(make::tokens::space(" ").into());
+                elements.push(field.syntax().clone().into());
+                insert_at(node, anchor.index() + 1, elements);
+            }
+        }
+
+        if trailing_comma {
+            field.add_separator();
+        }
+    }
+}
+
+impl StructField {
+    /// The separator after the field, if any.
+    pub fn separator(&self) -> Option<SyntaxToken> {
+        let mut next = self.syntax().next_sibling_or_token();
+        while let Some(element) = next {
+            match element.kind() {
+                SyntaxKind::Token(TokenKind::Comma | TokenKind::Semicolon) => {
+                    return element.into_token()
+                }
+                _ if is_trivia(&element) => next = element.next_sibling_or_token(),
+                _ => return None,
+            }
+        }
+        None
+    }
+
+    /// Adds a comma right after the field. The field must already be part
+    /// of a struct.
+    pub fn add_separator(&self) {
+        if self.separator().is_some() {
+            return;
+        }
+
+        let node = self.syntax();
+        let Some(parent) = node.parent() else {
+            return;
+        };
+
+        insert_at(
+            &parent,
+            node.index() + 1,
+            vec![make::tokens::comma().into()],
+        );
+    }
+
+    /// Removes the field together with its separator. When the field
+    /// occupies a line of its own, the whole line goes away, including a
+    /// trailing comment.
+    pub fn remove(&self) {
+        let node = self.syntax();
+        let Some(parent) = node.parent() else {
+            return;
+        };
+
+        let separator = self.separator();
+        let last: SyntaxElement = match &separator {
+            Some(separator) => separator.clone().into(),
+            None => node.clone().into(),
+        };
+
+        let mut start = node.index();
+        let mut end = last.index() + 1;
+        if line_indent(node).is_some() {
+            if node
+                .prev_sibling_or_token()
+                .is_some_and(|e| is_inline_space(&e))
+            {
+                start -= 1;
+            }
+
+            end = token_after(&last, is_inline_space);
+            if parent
+                .children_with_tokens()
+                .nth(end)
+                .is_some_and(|e| ends_line(&e))
+            {
+                end += 1;
+            }
+        } else if separator.is_some() {
+            end = token_after(&last, is_inline_space);
+        }
+
+        parent.splice_children(start..end, Vec::new());
+    }
+}
+
+impl Schema {
+    /// Inserts `item` after the last `use`, or at the top of the file.
+    pub fn add_use(&self, item: Use) {
+        let node = self.syntax();
+        match self.uses().last() {
+            Some(last) => insert_at(
+                node,
+                last.syntax().index() + 1,
+                vec![make::tokens::newline().into(), item.syntax().clone().into()],
+            ),
+            None => {
+                let mut elements =
+                    vec![item.syntax().clone().into(), make::tokens::newline().into()];
+                if node.first_child_or_token().is_some() {
+                    elements.push(make::tokens::newline().into());
+                }
+
+                insert_at(node, 0, elements);
+            }
+        }
+    }
+}
+
+impl Ident {
+    /// Replaces the identifier text, returning the new token.
+    pub fn rename(&self, name: &str) -> Ident {
+        let ident = make::ident(name);
+        let parent = self.0.parent().unwrap();
+        let index = self.0.index();
+        parent.splice_children(index..index + 1, vec![ident.0.clone().into()]);
+        ident
+    }
+}
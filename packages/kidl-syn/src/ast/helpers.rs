@@ -1,6 +1,7 @@
-use rowan::{ast::AstNode, NodeOrToken};
+use rowan::{ast::AstNode, NodeOrToken, TextRange, TextSize};
 
 use crate::{
+    helpers::ByteOffset,
     kind::{SyntaxKind, TokenKind},
     lang::KIDL,
 };
@@ -47,6 +48,90 @@ pub fn first_token_of<T: AstToken>(node: &SyntaxNode) -> Option<T> {
     })
 }
 
+/// The token at `offset`. Between two tokens, names and strings are
+/// preferred over punctuation and trivia, so that a cursor right after a
+/// name still finds it.
+pub fn token_at_offset(root: &SyntaxNode, offset: ByteOffset) -> Option<SyntaxToken> {
+    let offset = TextSize::try_from(offset.0).ok()?;
+    if offset > root.text_range().end() {
+        return None;
+    }
+
+    root.token_at_offset(offset)
+        .max_by_key(|token| match token.kind() {
+            SyntaxKind::Token(TokenKind::Ident | TokenKind::String) => 2,
+            SyntaxKind::Token(TokenKind::Space | TokenKind::NewLine | TokenKind::Comment) => 0,
+            _ => 1,
+        })
+}
+
+/// The comments on the lines right above `node`, without the `//`. A blank
+/// line ends them, and a comment that follows code on its line belongs to
+/// that code instead.
+pub fn doc_comment(node: &SyntaxNode) -> Option<String> {
+    let first = node
+        .descendants_with_tokens()
+        .filter_map(|e| e.into_token())
+        .find(|t| !is_trivia(t))?;
+
+    let mut lines = Vec::new();
+    let mut token = first.prev_token();
+    while let Some(current) = token {
+        match current.kind() {
+            SyntaxKind::Token(TokenKind::Space) => {}
+            SyntaxKind::Token(TokenKind::Comment) if !is_trailing(&current) => {
+                let text = current.text().trim_start_matches('/').trim_end();
+                lines.push(text.strip_prefix(' ').unwrap_or(text).to_owned());
+            }
+            _ => break,
+        }
+        token = current.prev_token();
+    }
+
+    if lines.is_empty() {
+        return None;
+    }
+
+    lines.reverse();
+    Some(lines.join("\n"))
+}
+
+/// Range of `node` without its leading and trailing trivia.
+pub fn trimmed_range(node: &SyntaxNode) -> TextRange {
+    let mut tokens = node
+        .descendants_with_tokens()
+        .filter_map(|e| e.into_token())
+        .filter(|t| !is_trivia(t));
+
+    match tokens.next() {
+        Some(first) => {
+            let last = tokens.last().unwrap_or_else(|| first.clone());
+            TextRange::new(first.text_range().start(), last.text_range().end())
+        }
+        None => node.text_range(),
+    }
+}
+
+fn is_trivia(token: &SyntaxToken) -> bool {
+    matches!(
+        token.kind(),
+        SyntaxKind::Token(TokenKind::Space | TokenKind::NewLine | TokenKind::Comment)
+    )
+}
+
+/// Whether `comment` comes after code on the same line.
+fn is_trailing(comment: &SyntaxToken) -> bool {
+    let mut token = comment.prev_token();
+    while let Some(current) = token {
+        match current.kind() {
+            SyntaxKind::Token(TokenKind::Space) => token = current.prev_token(),
+            SyntaxKind::Token(TokenKind::NewLine | TokenKind::Comment) => return false,
+            _ => return true,
+        }
+    }
+    false
+}
+
 pub trait HasName: AstNode<Language = KIDL> {
     fn name(&self) -> Option<Ident> {
         self.syntax()
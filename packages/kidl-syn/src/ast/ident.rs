@@ -2,7 +2,7 @@ use crate::kind::TokenKind;
 
 use super::{helpers::AstToken, SyntaxToken};
 
-#[derive(PartialEq, Eq, Hash)]
+#[derive(PartialEq, Eq, Hash, Clone, Debug)]
 #[repr(transparent)]
 pub struct Ident(pub SyntaxToken);
 
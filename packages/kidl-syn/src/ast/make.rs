@@ -0,0 +1,129 @@
+//! Constructors for syntax trees.
+//!
+//! Nodes are built by parsing a snippet of source text, so they always have
+//! the shape the parser would produce. Everything returned from here is a
+//! mutable tree (see [`rowan::SyntaxNode::clone_for_update`]) and can be
+//! inserted into another mutable tree with the helpers from [`super::edit`].
+
+use rowan::{ast::AstNode, GreenNode, GreenToken};
+
+use crate::{
+    kind::{NodeKind, TokenKind},
+    lang::KIDL,
+    lexer::tokenize,
+    parser::parse_str,
+    source::StrSource,
+};
+
+use super::{
+    ident::Ident, r#struct::Struct, r#struct::StructField, r#type::Type, r#use::Use,
+    schema::Schema, SyntaxNode, SyntaxToken,
+};
+
+fn ast_from_text<N: AstNode<Language = KIDL>>(text: &str) -> N {
+    let parsed = parse_str(text);
+    let root = SyntaxNode::new_root(parsed.schema);
+    let node = root.descendants().find_map(N::cast).unwrap_or_else(|| {
+        panic!(
+            "failed to make {} from {:?}",
+            std::any::type_name::<N>(),
+            text
+        )
+    });
+
+    N::cast(node.syntax().clone_subtree().clone_for_update()).unwrap()
+}
+
+pub fn schema(text: &str) -> Schema {
+    let parsed = parse_str(text);
+    Schema::cast(SyntaxNode::new_root(parsed.schema).clone_for_update()).unwrap()
+}
+
+pub fn ident(name: &str) -> Ident {
+    Ident(tokens::token(TokenKind::Ident, name))
+}
+
+pub fn ty(name: &str, args: impl IntoIterator<Item = Type>) -> Type {
+    let args = args
+        .into_iter()
+        .map(|a| a.0.to_string())
+        .collect::<Vec<_>>();
+
+    if args.is_empty() {
+        ast_from_text(&format!("struct S {{ f: {}}}", name))
+    } else {
+        ast_from_text(&format!("struct S {{ f: {}<{}>}}", name, args.join(", ")))
+    }
+}
+
+pub fn struct_field(name: &str, ty: Type, optional: bool) -> StructField {
+    let question = if optional { "?" } else { "" };
+    ast_from_text(&format!("struct S {{ {}{}: {}}}", name, question, ty.0))
+}
+
+pub fn struct_(name: &str, fields: impl IntoIterator<Item = StructField>) -> Struct {
+    let fields = fields
+        .into_iter()
+        .map(|f| format!("    {},\n", f.0.to_string().trim_end_matches(',')))
+        .collect::<String>();
+
+    ast_from_text(&format!("struct {} {{\n{}}}", name, fields))
+}
+
+/// Makes a `use` item from path segments. Segments that are not valid
+/// identifiers (e.g. file paths) are quoted.
+pub fn use_<'a>(segments: impl IntoIterator<Item = &'a str>) -> Use {
+    let path = segments
+        .into_iter()
+        .map(|s| {
+            let mut chars = s.chars();
+            let is_ident = chars.next().is_some_and(unicode_ident::is_xid_start)
+                && chars.all(unicode_ident::is_xid_continue);
+
+            if is_ident {
+                s.to_owned()
+            } else {
+                format!("{:?}", s)
+            }
+        })
+        .collect::<Vec<_>>();
+
+    ast_from_text(&format!("use {};", path.join("::")))
+}
+
+pub mod tokens {
+    use super::*;
+
+    /// Makes a standalone token of the given kind. The text is not validated.
+    pub fn token(kind: TokenKind, text: &str) -> SyntaxToken {
+        let green = GreenNode::new(
+            NodeKind::Root.into(),
+            [GreenToken::new(kind.into(), text).into()],
+        );
+
+        SyntaxNode::new_root(green)
+            .clone_for_update()
+            .first_token()
+            .unwrap()
+    }
+
+    /// Lexes `text` into tokens, e.g. to build a run of whitespace.
+    pub fn from_text(text: &str) -> Vec<SyntaxToken> {
+        tokenize(StrSource::new(text))
+            .map(|t| token(t.kind, &t.slice))
+            .collect()
+    }
+
+    pub fn comma() -> SyntaxToken {
+        token(TokenKind::Comma, ",")
+    }
+
+    pub fn newline() -> SyntaxToken {
+        token(TokenKind::NewLine, "\n")
+    }
+
+    pub fn space(text: &str) -> SyntaxToken {
+        debug_assert!(text.chars().all(|c| c.is_whitespace() && c != '\n'));
+        token(TokenKind::Space, text)
+    }
+}
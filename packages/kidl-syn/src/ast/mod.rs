@@ -3,11 +3,15 @@ use crate::lang::KIDL;
 #[macro_use]
 pub mod macros;
 
-mod helpers;
-mod ident;
+pub mod edit;
+pub mod helpers;
+pub mod ident;
+pub mod make;
+pub mod path;
 pub mod schema;
 pub mod r#struct;
 pub mod r#type;
+pub mod r#use;
 
 pub use rowan::ast::AstNode;
 pub use rowan::NodeOrToken;
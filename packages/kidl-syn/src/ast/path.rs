@@ -0,0 +1,55 @@
+use crate::kind::{NodeKind, SyntaxKind, TokenKind};
+
+use super::{ident::Ident, SyntaxNode, SyntaxToken};
+
+#[derive(PartialEq, Eq, Hash, Clone, Debug)]
+#[repr(transparent)]
+pub struct Path(pub SyntaxNode);
+
+ast_node!(Path, SyntaxKind::Node(NodeKind::Path));
+
+#[derive(PartialEq, Eq, Hash, Clone, Debug)]
+pub enum PathSegment {
+    Ident(Ident),
+    /// A quoted segment, e.g. a file path or an URL.
+    String(SyntaxToken),
+}
+
+impl PathSegment {
+    pub fn syntax(&self) -> &SyntaxToken {
+        match self {
+            PathSegment::Ident(ident) => &ident.0,
+            PathSegment::String(token) => token,
+        }
+    }
+
+    /// Text of the segment with the quotes removed.
+    pub fn text(&self) -> &str {
+        match self {
+            PathSegment::Ident(ident) => ident.text(),
+            PathSegment::String(token) => {
+                let text = token.text();
+                let text = text.strip_prefix(['"', '\'']).unwrap_or(text);
+                text.strip_suffix(['"', '\'']).unwrap_or(text)
+            }
+        }
+    }
+}
+
+impl Path {
+    pub fn segments(&self) -> impl Iterator<Item = PathSegment> {
+        self.0
+            .children_with_tokens()
+            .filter_map(|t| t.into_token())
+            .filter_map(|t| match t.kind() {
+                SyntaxKind::Token(TokenKind::Ident) => Some(PathSegment::Ident(Ident(t))),
+                SyntaxKind::Token(TokenKind::String) => Some(PathSegment::String(t)),
+                _ => None,
+            })
+    }
+
+    /// The last segment, which names the imported item.
+    pub fn name(&self) -> Option<PathSegment> {
+        self.segments().last()
+    }
+}
@@ -1,6 +1,6 @@
 use crate::kind::{NodeKind, SyntaxKind};
 
-use super::{r#struct::Struct, SyntaxNode};
+use super::{helpers::filter_all, r#struct::Struct, r#use::Use, SyntaxNode};
 
 #[derive(PartialEq, Eq, Hash, Clone, Debug)]
 #[repr(transparent)]
@@ -19,4 +19,8 @@ impl Schema {
             _ => None,
         })
     }
+
+    pub fn uses(&self) -> impl Iterator<Item = Use> {
+        filter_all::<Use>(&self.0)
+    }
 }
@@ -8,7 +8,7 @@ use super::{
     SyntaxNode, SyntaxToken,
 };
 
-#[derive(PartialEq, Eq, Hash)]
+#[derive(PartialEq, Eq, Hash, Clone, Debug)]
 #[repr(transparent)]
 pub struct Struct(pub SyntaxNode);
 
@@ -22,7 +22,7 @@ impl Struct {
     }
 }
 
-#[derive(PartialEq, Eq, Hash)]
+#[derive(PartialEq, Eq, Hash, Clone, Debug)]
 #[repr(transparent)]
 pub struct StructField(pub SyntaxNode);
 
@@ -1,11 +1,24 @@
 use crate::kind::{NodeKind, SyntaxKind};
 
-use super::{helpers::HasName, SyntaxNode};
+use super::{
+    helpers::{filter_all, HasName},
+    SyntaxNode,
+};
 
-#[derive(PartialEq, Eq, Hash)]
+#[derive(PartialEq, Eq, Hash, Clone, Debug)]
 #[repr(transparent)]
 pub struct Type(pub SyntaxNode);
 
 ast_node!(Type, SyntaxKind::Node(NodeKind::Type));
 
 impl HasName for Type {}
+
+impl Type {
+    /// Type arguments, e.g. `K` and `V` of `Map<K, V>`.
+    pub fn arguments(&self) -> impl Iterator<Item = Type> {
+        self.0
+            .children()
+            .filter(|c| c.kind() == SyntaxKind::Node(NodeKind::TypeArguments))
+            .flat_map(|args| filter_all::<Type>(&args))
+    }
+}
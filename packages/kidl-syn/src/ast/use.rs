@@ -0,0 +1,17 @@
+use rowan::ast::AstNode;
+
+use crate::kind::{NodeKind, SyntaxKind};
+
+use super::{helpers::find_of, path::Path, SyntaxNode};
+
+#[derive(PartialEq, Eq, Hash, Clone, Debug)]
+#[repr(transparent)]
+pub struct Use(pub SyntaxNode);
+
+ast_node!(Use, SyntaxKind::Node(NodeKind::Use));
+
+impl Use {
+    pub fn path(&self) -> Option<Path> {
+        find_of::<Path>(self.syntax())
+    }
+}
@@ -33,7 +33,11 @@ impl std::fmt::Debug for DebugTokenPrinter {
 
         match kind {
             SyntaxKind::Token(
-                TokenKind::Ident | TokenKind::Number | TokenKind::Comment | TokenKind::String,
+                TokenKind::Ident
+                | TokenKind::Number
+                | TokenKind::Comment
+                | TokenKind::String
+                | TokenKind::Unknown,
             ) => write!(f, "{:?}[{:?}]", kind, self.0.text()),
             _ => {
                 write!(f, "{:?}", kind)
@@ -0,0 +1,579 @@
+//! Canonical formatting of schema files.
+//!
+//! The formatter walks the lossless syntax tree, so every comment of the
+//! input ends up in the output. Formatting an already formatted file is a
+//! no-op.
+
+use rowan::ast::AstNode;
+
+use crate::{
+    ast::{schema::Schema, NodeOrToken, SyntaxElement, SyntaxNode, SyntaxToken},
+    kind::{NodeKind, SyntaxKind, TokenKind},
+    parser::{parse_str_with_options, ListSeparators, ParseOptions, SyntaxError},
+};
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum TrailingCommas {
+    /// Put a comma after the last item of every multi-line list.
+    Always,
+    /// Never put a comma after the last item.
+    Never,
+}
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct FormatConfig {
+    pub indent_width: usize,
+    /// Indent with a tab per level instead of `indent_width` spaces. Tabs
+    /// still count as `indent_width` columns towards `max_width`.
+    pub use_tabs: bool,
+    /// Type argument lists that do not fit are split one per line.
+    pub max_width: usize,
+    pub trailing_commas: TrailingCommas,
+    /// Separators the struct fields are parsed with. Fields are written
+    /// with a comma if it is one of them, else with a semicolon, and
+    /// with a line break alone otherwise.
+    pub field_separators: ListSeparators,
+}
+
+impl Default for FormatConfig {
+    fn default() -> Self {
+        FormatConfig {
+            indent_width: 4,
+            use_tabs: false,
+            max_width: 100,
+            trailing_commas: TrailingCommas::Always,
+            field_separators: ListSeparators::COMMA,
+        }
+    }
+}
+
+/// Formats `text`. Files with syntax errors are left alone and the errors
+/// are returned instead. Lints don't prevent formatting.
+pub fn format_str(text: &str, config: &FormatConfig) -> Result<String, Vec<SyntaxError>> {
+    let options = ParseOptions {
+        field_separators: config.field_separators,
+    };
+    let parsed = parse_str_with_options(text, options);
+    if parsed.has_errors() {
+        return Err(parsed.errors);
+    }
+
+    let schema = Schema::cast(SyntaxNode::new_root(parsed.schema)).unwrap();
+    Ok(format(&schema, config))
+}
+
+pub fn format(schema: &Schema, config: &FormatConfig) -> String {
+    let mut printer = Printer {
+        config,
+        out: String::new(),
+        indent: 0,
+    };
+
+    printer.schema(schema.syntax());
+    printer.out
+}
+
+fn is_trivia(kind: SyntaxKind) -> bool {
+    matches!(
+        kind,
+        SyntaxKind::Token(TokenKind::Space | TokenKind::NewLine | TokenKind::Comment)
+    )
+}
+
+fn newlines(token: &SyntaxToken) -> usize {
+    match token.kind() {
+        SyntaxKind::Token(TokenKind::Comment) => token.text().ends_with('\n') as usize,
+        _ => token.text().matches('\n').count(),
+    }
+}
+
+/// A comment together with the number of line breaks in front of it.
+struct Comment {
+    text: String,
+    newlines_before: usize,
+}
+
+/// Comments of a trivia run and the line breaks after the last of them.
+#[derive(Default)]
+struct Trivia {
+    comments: Vec<Comment>,
+    newlines_after: usize,
+}
+
+impl Trivia {
+    fn push(&mut self, token: &SyntaxToken) {
+        match token.kind() {
+            SyntaxKind::Token(TokenKind::Comment) => {
+                self.comments.push(Comment {
+                    text: token.text().trim_end().to_owned(),
+                    newlines_before: self.newlines_after,
+                });
+                self.newlines_after = newlines(token);
+            }
+            _ => self.newlines_after += newlines(token),
+        }
+    }
+
+    fn extend<'a>(&mut self, tokens: impl IntoIterator<Item = &'a SyntaxToken>) {
+        tokens.into_iter().for_each(|t| self.push(t))
+    }
+
+    fn blank_line_before(&self, index: usize) -> bool {
+        self.comments[index].newlines_before > 1
+    }
+
+    fn blank_line_after(&self) -> bool {
+        self.newlines_after > 1
+    }
+
+    /// Whether there is a blank line before the first comment or, if there
+    /// are no comments, before the next token.
+    fn starts_with_blank_line(&self) -> bool {
+        match self.comments.first() {
+            Some(first) => first.newlines_before > 1,
+            None => self.newlines_after > 1,
+        }
+    }
+
+    /// Splits off the comment that continues the line of the previous token.
+    fn split_trailing(self) -> (Trivia, Trivia) {
+        let mut trailing = Trivia::default();
+        let mut leading = Trivia {
+            comments: Vec::new(),
+            newlines_after: self.newlines_after,
+        };
+
+        for comment in self.comments {
+            if comment.newlines_before == 0 && leading.comments.is_empty() {
+                trailing.comments.push(comment);
+            } else {
+                leading.comments.push(comment);
+            }
+        }
+
+        (trailing, leading)
+    }
+
+    fn is_empty(&self) -> bool {
+        self.comments.is_empty()
+    }
+}
+
+/// Leading trivia, significant children and trailing trivia of a node.
+fn split_trivia(node: &SyntaxNode) -> (Vec<SyntaxToken>, Vec<SyntaxElement>, Vec<SyntaxToken>) {
+    let mut children = node.children_with_tokens().collect::<Vec<_>>();
+
+    let trailing = children
+        .iter()
+        .rev()
+        .take_while(|e| is_trivia(e.kind()))
+        .count();
+    let trailing = children
+        .split_off(children.len() - trailing)
+        .into_iter()
+        .filter_map(|e| e.into_token())
+        .collect();
+
+    let leading = children.iter().take_while(|e| is_trivia(e.kind())).count();
+    let rest = children.split_off(leading);
+    let leading = children
+        .into_iter()
+        .filter_map(|e| e.into_token())
+        .collect();
+
+    (leading, rest, trailing)
+}
+
+/// Comments nested anywhere inside `elements`.
+fn inner_comments(elements: &[SyntaxElement]) -> Vec<SyntaxToken> {
+    elements
+        .iter()
+        .flat_map(|e| match e {
+            NodeOrToken::Node(node) => node
+                .descendants_with_tokens()
+                .filter_map(|e| e.into_token())
+                .collect(),
+            NodeOrToken::Token(token) => vec![token.clone()],
+        })
+        .filter(|t| t.kind() == SyntaxKind::Token(TokenKind::Comment))
+        .collect()
+}
+
+struct Printer<'c> {
+    config: &'c FormatConfig,
+    out: String,
+    indent: usize,
+}
+
+impl<'c> Printer<'c> {
+    fn newline(&mut self) {
+        let trimmed = self.out.trim_end_matches([' ', '\t']).len();
+        self.out.truncate(trimmed);
+        self.out.push('\n');
+    }
+
+    fn blank_line(&mut self) {
+        if !self.out.is_empty() && !self.out.ends_with("\n\n") {
+            self.newline();
+        }
+    }
+
+    fn write_indent(&mut self) {
+        let indent = match self.config.use_tabs {
+            true => "\t".repeat(self.indent),
+            false => " ".repeat(self.indent * self.config.indent_width),
+        };
+        self.out.push_str(&indent);
+    }
+
+    fn at_line_start(&self) -> bool {
+        self.out.is_empty() || self.out.ends_with('\n')
+    }
+
+    fn current_column(&self) -> usize {
+        let line_start = self.out.rfind('\n').map(|i| i + 1).unwrap_or(0);
+        self.out[line_start..]
+            .chars()
+            .map(|c| match c {
+                '\t' => self.config.indent_width,
+                _ => 1,
+            })
+            .sum()
+    }
+
+    /// Prints comments of `trivia` that precede an item. A comment that
+    /// follows code on the same line stays there.
+    fn comments(&mut self, trivia: &Trivia) {
+        for (index, comment) in trivia.comments.iter().enumerate() {
+            if comment.newlines_before == 0 && !self.at_line_start() {
+                self.out.push(' ');
+                self.out.push_str(&comment.text);
+                self.newline();
+                continue;
+            }
+
+            if !self.at_line_start() {
+                self.newline();
+            }
+
+            if trivia.blank_line_before(index) {
+                self.blank_line();
+            }
+
+            self.write_indent();
+            self.out.push_str(&comment.text);
+            self.newline();
+        }
+    }
+
+    fn schema(&mut self, node: &SyntaxNode) {
+        let mut trivia = Trivia::default();
+        let mut prev: Option<NodeKind> = None;
+
+        for element in node.children_with_tokens() {
+            match element {
+                NodeOrToken::Token(token) => trivia.push(&token),
+                NodeOrToken::Node(node) => {
+                    let SyntaxKind::Node(kind) = node.kind() else {
+                        unreachable!()
+                    };
+
+                    let (leading, rest, trailing) = split_trivia(&node);
+                    trivia.extend(&leading);
+
+                    self.item_separator(prev, kind, trivia);
+                    self.item(kind, &rest);
+                    prev = Some(kind);
+
+                    trivia = Trivia::default();
+                    trivia.extend(&trailing);
+                }
+            }
+        }
+
+        self.comments(&trivia);
+        if !self.at_line_start() {
+            self.newline();
+        }
+    }
+
+    fn item_separator(&mut self, prev: Option<NodeKind>, next: NodeKind, trivia: Trivia) {
+        let Some(prev) = prev else {
+            self.comments(&trivia);
+            if !trivia.is_empty() && trivia.blank_line_after() {
+                self.blank_line();
+            }
+            return;
+        };
+
+        // Only consecutive `use` items may go without a blank line
+        let blank_line = prev != NodeKind::Use || next != NodeKind::Use;
+
+        let (trailing, mut leading) = trivia.split_trailing();
+        self.comments(&trailing);
+        if !self.at_line_start() {
+            self.newline();
+        }
+
+        if blank_line || leading.starts_with_blank_line() {
+            self.blank_line();
+        }
+
+        if let Some(first) = leading.comments.first_mut() {
+            // The blank line, if any, is already printed
+            first.newlines_before = 1;
+        }
+
+        self.comments(&leading);
+        if !leading.is_empty() && leading.blank_line_after() {
+            self.blank_line();
+        }
+    }
+
+    fn item(&mut self, kind: NodeKind, children: &[SyntaxElement]) {
+        match kind {
+            NodeKind::Use => self.use_(children),
+            NodeKind::Struct => self.struct_(children),
+            _ => children
+                .iter()
+                .for_each(|c| self.out.push_str(&c.to_string())),
+        }
+    }
+
+    fn use_(&mut self, children: &[SyntaxElement]) {
+        self.hoisted_comments(&inner_comments(children));
+        self.write_indent();
+        self.out.push_str("use ");
+
+        for child in children {
+            if let NodeOrToken::Node(path) = child {
+                let mut first = true;
+                let segments = path
+                    .children_with_tokens()
+                    .filter_map(|e| e.into_token())
+                    .filter(|t| {
+                        matches!(
+                            t.kind(),
+                            SyntaxKind::Token(TokenKind::Ident | TokenKind::String)
+                        )
+                    });
+
+                for token in segments {
+                    if !first {
+                        self.out.push_str("::");
+                    }
+                    first = false;
+                    self.out.push_str(token.text());
+                }
+            }
+        }
+
+        self.out.push(';');
+    }
+
+    /// Prints comments that sit in the middle of an item on their own
+    /// lines in front of the item.
+    fn hoisted_comments(&mut self, comments: &[SyntaxToken]) {
+        for comment in comments {
+            self.write_indent();
+            self.out.push_str(comment.text().trim_end());
+            self.newline();
+        }
+    }
+
+    fn struct_(&mut self, children: &[SyntaxElement]) {
+        let header = children
+            .iter()
+            .position(|c| c.kind() == SyntaxKind::Token(TokenKind::CurlyOpen))
+            .unwrap_or(children.len());
+        self.hoisted_comments(&inner_comments(&children[..header]));
+
+        self.write_indent();
+        self.out.push_str("struct");
+
+        let mut trivia = Trivia::default();
+        let mut fields = Vec::new();
+        let mut body = false;
+
+        for child in children {
+            match child {
+                NodeOrToken::Token(token) => match token.kind() {
+                    kind if is_trivia(kind) && body => trivia.push(token),
+                    kind if is_trivia(kind) => {}
+                    SyntaxKind::Token(TokenKind::Ident) if token.text() == "struct" => {}
+                    SyntaxKind::Token(TokenKind::Ident) => {
+                        self.out.push(' ');
+                        self.out.push_str(token.text());
+                    }
+                    SyntaxKind::Token(TokenKind::CurlyOpen) => {
+                        self.out.push_str(" {");
+                        body = true;
+                    }
+                    SyntaxKind::Token(TokenKind::CurlyClose) => {
+                        fields.push((std::mem::take(&mut trivia), None));
+                    }
+                    _ => {}
+                },
+                NodeOrToken::Node(field) => {
+                    // A field without a separator ends with the trivia after it
+                    let (leading, _, trailing) = split_trivia(field);
+                    trivia.extend(&leading);
+                    fields.push((std::mem::take(&mut trivia), Some(field.clone())));
+                    trivia.extend(&trailing);
+                }
+            }
+        }
+
+        if fields
+            .iter()
+            .all(|(trivia, field)| trivia.is_empty() && field.is_none())
+        {
+            self.out.push('}');
+            return;
+        }
+
+        self.indent += 1;
+        let count = fields.iter().filter(|(_, f)| f.is_some()).count();
+        let mut index = 0;
+
+        for (trivia, field) in fields {
+            // Comments right after the previous field stay on its line
+            let (trailing, mut leading) = trivia.split_trailing();
+            self.comments(&trailing);
+            if !self.at_line_start() {
+                self.newline();
+            }
+
+            // No blank lines right after the opening brace
+            if leading.starts_with_blank_line() && index > 0 {
+                self.blank_line();
+            }
+
+            if let Some(first) = leading.comments.first_mut() {
+                first.newlines_before = 1;
+            }
+
+            let has_comments = !leading.is_empty();
+            self.comments(&leading);
+
+            let Some(field) = field else {
+                break;
+            };
+
+            if has_comments && leading.blank_line_after() {
+                self.blank_line();
+            }
+
+            index += 1;
+            self.field(&field, index == count);
+        }
+
+        self.indent -= 1;
+        if !self.at_line_start() {
+            self.newline();
+        }
+        self.write_indent();
+        self.out.push('}');
+    }
+
+    fn field(&mut self, node: &SyntaxNode, last: bool) {
+        let (_, children, _) = split_trivia(node);
+        self.hoisted_comments(&inner_comments(&children));
+        self.write_indent();
+
+        let mut ty = None;
+        for child in &children {
+            match child {
+                NodeOrToken::Token(token) => match token.kind() {
+                    SyntaxKind::Token(TokenKind::Ident) => self.out.push_str(token.text()),
+                    SyntaxKind::Token(TokenKind::Question) => self.out.push('?'),
+                    SyntaxKind::Token(TokenKind::Colon) => self.out.push_str(": "),
+                    _ => {}
+                },
+                NodeOrToken::Node(node) => ty = Some(node.clone()),
+            }
+        }
+
+        let separators = self.config.field_separators;
+        let separator = match (separators.comma, separators.semicolon) {
+            (true, _) => Some(','),
+            (false, true) => Some(';'),
+            (false, false) => None,
+        }
+        .filter(|_| !last || self.config.trailing_commas == TrailingCommas::Always);
+
+        if let Some(ty) = ty {
+            self.ty(&ty, separator.is_some() as usize);
+        }
+
+        if let Some(separator) = separator {
+            self.out.push(separator);
+        }
+    }
+
+    fn flat_type(node: &SyntaxNode) -> String {
+        let mut out = String::new();
+        for child in node.children_with_tokens() {
+            match child {
+                NodeOrToken::Token(token) => {
+                    if token.kind() == SyntaxKind::Token(TokenKind::Ident) {
+                        out.push_str(token.text());
+                    }
+                }
+                NodeOrToken::Node(args) => {
+                    let args = args
+                        .children()
+                        .map(|a| Self::flat_type(&a))
+                        .collect::<Vec<_>>();
+                    out.push('<');
+                    out.push_str(&args.join(", "));
+                    out.push('>');
+                }
+            }
+        }
+        out
+    }
+
+    /// Prints a type, splitting its arguments over several lines when the
+    /// line would otherwise exceed the maximum width. `suffix` is the width
+    /// of what follows the type on the same line.
+    fn ty(&mut self, node: &SyntaxNode, suffix: usize) {
+        let flat = Self::flat_type(node);
+        let args = node
+            .children()
+            .find(|c| c.kind() == SyntaxKind::Node(NodeKind::TypeArguments));
+
+        let fits = self.current_column() + flat.chars().count() + suffix <= self.config.max_width;
+        let Some(args) = args.filter(|_| !fits) else {
+            self.out.push_str(&flat);
+            return;
+        };
+
+        for token in node.children_with_tokens().filter_map(|e| e.into_token()) {
+            if token.kind() == SyntaxKind::Token(TokenKind::Ident) {
+                self.out.push_str(token.text());
+            }
+        }
+
+        self.out.push('<');
+        self.newline();
+        self.indent += 1;
+
+        let args = args.children().collect::<Vec<_>>();
+        for (index, arg) in args.iter().enumerate() {
+            let last = index + 1 == args.len();
+            let comma = !last || self.config.trailing_commas == TrailingCommas::Always;
+
+            self.write_indent();
+            self.ty(arg, comma as usize);
+            if comma {
+                self.out.push(',');
+            }
+            self.newline();
+        }
+
+        self.indent -= 1;
+        self.write_indent();
+        self.out.push('>');
+    }
+}
@@ -29,6 +29,8 @@ pub enum TokenKind {
     Ident,
     String,
     Number,
+    /// A character that doesn't start any token.
+    Unknown,
 }
 
 #[derive(Debug, Clone, Copy, PartialEq, Eq, PartialOrd, Ord, Hash)]
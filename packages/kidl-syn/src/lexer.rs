@@ -125,7 +125,7 @@ impl<'a, S: Source<'a>> Iterator for Lexer<'a, S> {
                     self.consume_while(|c| unicode_ident::is_xid_continue(c));
                     TokenKind::Ident
                 }
-                _ => todo!(),
+                _ => TokenKind::Unknown,
             };
 
             let next_offset = self.offset + self.consumed;
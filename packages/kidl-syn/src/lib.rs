@@ -1,5 +1,6 @@
 pub mod ast;
 pub mod debug;
+pub mod format;
 pub mod helpers;
 pub mod kind;
 pub mod lang;
@@ -9,3 +9,6 @@ mod r#type;
 mod r#use;
 
 pub use parser::*;
+pub use utility::combinators::ListSeparators;
+pub use utility::error::{Severity, SyntaxError};
+pub use utility::keyword::is_identifier;
@@ -11,7 +11,10 @@ use crate::{
 
 pub use rowan::NodeCache;
 
-use super::utility::{combinators::ExpectPredicate, error::SyntaxError};
+use super::utility::{
+    combinators::{ExpectPredicate, ListSeparators},
+    error::SyntaxError,
+};
 
 pub trait TokenIter<'t>: Iterator<Item = Token<'t>> {}
 impl<'t, T> TokenIter<'t> for T where T: Iterator<Item = Token<'t>> {}
@@ -20,6 +23,7 @@ pub struct Parser<'c, 't, T: TokenIter<'t>> {
     pub(crate) builder: GreenNodeBuilder<'c>,
     pub(crate) tokens: Peekable<T>,
     pub(crate) errors: Vec<SyntaxError>,
+    pub(crate) options: ParseOptions,
     offset: ByteOffset,
     #[cfg(debug_assertions)]
     parsed: String,
@@ -110,13 +114,22 @@ impl<'c, 't, T: TokenIter<'t>> Parser<'c, 't, T> {
         }
     }
 
-    pub fn consume_trivia(&mut self) {
+    /// Consumes trivia including newlines, returns whether there was
+    /// a line break.
+    pub fn consume_trivia(&mut self) -> bool {
+        let mut line_break = false;
         while let Some(token) = self.tokens.peek() {
             match token.kind {
-                trivia_with_newline!() => self.consume_next(),
-                _ => return,
+                trivia_with_newline!() => {
+                    line_break |= token.kind == TokenKind::NewLine
+                        || token.kind == TokenKind::Comment
+                        || token.slice.contains('\n');
+                    self.consume_next()
+                }
+                _ => break,
             }
         }
+        line_break
     }
 
     pub fn consume_trivia_until_nl(&mut self) {
@@ -137,11 +150,16 @@ impl<'c, 't, T: TokenIter<'t>> Parser<'c, 't, T> {
         }
     }
 
+    /// Reports `token` and keeps it in the tree wrapped into an error node.
     pub fn unexpected(&mut self, token: Token<'t>) {
         self.errors.push(SyntaxError::new_at_offset(
             format!("Unexpected {:?}", token),
             self.offset,
-        ))
+        ));
+
+        self.builder.start_node(NodeKind::Error.into());
+        self.consume(token);
+        self.builder.finish_node();
     }
 
     pub fn error(&mut self, msg: impl Into<String>) {
@@ -149,6 +167,11 @@ impl<'c, 't, T: TokenIter<'t>> Parser<'c, 't, T> {
             .push(SyntaxError::new_at_offset(msg, self.offset))
     }
 
+    pub fn lint(&mut self, msg: impl Into<String>) {
+        self.errors
+            .push(SyntaxError::lint_at_offset(msg, self.offset))
+    }
+
     pub fn expected(&mut self, expected: &[TokenKind], got: TokenKind) {
         self.errors.push(SyntaxError::new_at_offset(
             format!("Expected [{:?}], got {:?}", expected, got),
@@ -157,12 +180,33 @@ impl<'c, 't, T: TokenIter<'t>> Parser<'c, 't, T> {
     }
 }
 
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, Hash)]
+pub struct ParseOptions {
+    /// Separators accepted between struct fields.
+    pub field_separators: ListSeparators,
+}
+
 pub struct Parsed {
     pub schema: GreenNode,
     pub errors: Vec<SyntaxError>,
 }
 
+impl Parsed {
+    /// Whether there are errors other than lints.
+    pub fn has_errors(&self) -> bool {
+        self.errors.iter().any(|e| !e.is_lint())
+    }
+}
+
 pub fn parse<'c, 't>(tokens: impl TokenIter<'t>, cache: &'c mut rowan::NodeCache) -> Parsed {
+    parse_with_options(tokens, cache, ParseOptions::default())
+}
+
+pub fn parse_with_options<'c, 't>(
+    tokens: impl TokenIter<'t>,
+    cache: &'c mut rowan::NodeCache,
+    options: ParseOptions,
+) -> Parsed {
     let parser = Parser {
         builder: GreenNodeBuilder::with_cache(cache),
         tokens: tokens.peekable(),
@@ -171,11 +215,20 @@ pub fn parse<'c, 't>(tokens: impl TokenIter<'t>, cache: &'c mut rowan::NodeCache
         offset: ByteOffset(0),
         _t: PhantomData,
         errors: Vec::new(),
+        options,
     };
 
     parser.parse_schema()
 }
 
 pub fn parse_str<'a>(source: &'a str) -> Parsed {
-    parse(tokenize(StrSource::new(source)), &mut NodeCache::default())
+    parse_str_with_options(source, ParseOptions::default())
+}
+
+pub fn parse_str_with_options(source: &str, options: ParseOptions) -> Parsed {
+    parse_with_options(
+        tokenize(StrSource::new(source)),
+        &mut NodeCache::default(),
+        options,
+    )
 }
@@ -20,9 +20,15 @@ impl<'c, 't, T: TokenIter<'t>> Parser<'c, 't, T> {
                         self.parse_struct(checkpoint, token);
                         checkpoint = self.builder.checkpoint();
                     }
-                    _ => self.unexpected(token),
+                    _ => {
+                        self.unexpected(token);
+                        checkpoint = self.builder.checkpoint();
+                    }
                 },
-                _ => self.unexpected(token),
+                _ => {
+                    self.unexpected(token);
+                    checkpoint = self.builder.checkpoint();
+                }
             }
         }
 
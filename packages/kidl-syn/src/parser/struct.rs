@@ -4,7 +4,10 @@ use crate::{
     kind::{NodeKind, TokenKind},
     lexer::Token,
     parser::{
-        utility::combinators::{kind, TRIVIA_NL},
+        utility::{
+            combinators::{kind, Delimited, TRIVIA_NL},
+            keyword::is_keyword,
+        },
         Parser,
     },
 };
@@ -18,38 +21,44 @@ impl<'c, 't, T: TokenIter<'t>> Parser<'c, 't, T> {
 
         debug_assert_eq!(token.slice, "struct");
         self.consume(token);
+        self.consume_trivia();
 
-        self.expect(kind(TokenKind::CurlyOpen), &TRIVIA_NL, |t| match t.kind {
-            TokenKind::CurlyClose => true,
-            _ => false,
+        self.expect(kind(TokenKind::Ident), &TRIVIA_NL, |t| {
+            matches!(t.kind, TokenKind::CurlyOpen | TokenKind::CurlyClose)
         });
 
-        while let Some(token) = self.tokens.next() {
-            match token.kind {
-                trivia_with_newline!() => self.consume(token),
-                TokenKind::Ident => self.parse_struct_field(token, TokenKind::Comma),
-                TokenKind::CurlyClose => {
-                    self.consume(token);
-                    break;
-                }
-                _ => self.unexpected(token),
-            }
-        }
+        self.expect(kind(TokenKind::CurlyOpen), &TRIVIA_NL, |t| {
+            t.kind == TokenKind::CurlyClose
+        });
+
+        let list = Delimited {
+            close: TokenKind::CurlyClose,
+            separators: self.options.field_separators,
+            recovery: is_keyword,
+        };
+
+        self.delimited_list(
+            &list,
+            |t| t.kind == TokenKind::Ident && !is_keyword(t),
+            |p, token| p.parse_struct_field(token),
+        );
 
         self.consume_trivia_until_nl();
         self.builder.finish_node();
     }
 
-    pub(crate) fn parse_struct_field(&mut self, token: Token<'t>, separator: TokenKind) {
+    pub(crate) fn parse_struct_field(&mut self, token: Token<'t>) {
         self.builder.start_node(NodeKind::StructField.into());
         self.consume(token);
         self.consume_trivia();
         self.consume_maybe(TokenKind::Question);
         self.consume_trivia();
 
-        self.expect(kind(TokenKind::Colon), &TRIVIA_NL, |t| match t.kind {
-            TokenKind::Comma | TokenKind::CurlyClose => true,
-            _ => false,
+        self.expect(kind(TokenKind::Colon), &TRIVIA_NL, |t| {
+            matches!(
+                t.kind,
+                TokenKind::Comma | TokenKind::Semicolon | TokenKind::CurlyClose
+            )
         });
 
         self.consume_trivia();
@@ -57,8 +66,6 @@ impl<'c, 't, T: TokenIter<'t>> Parser<'c, 't, T> {
             self.parse_type(ident);
         }
 
-        self.consume_trivia();
-        self.consume_maybe(separator);
         self.builder.finish_node()
     }
 }
@@ -5,10 +5,24 @@ use crate::{
 };
 
 use super::{
-    utility::combinators::{kind, TRIVIA_NL},
+    utility::{
+        combinators::{Delimited, ListSeparators},
+        keyword::is_keyword,
+    },
     TokenIter,
 };
 
+const TYPE_ARGUMENTS: Delimited = Delimited {
+    close: TokenKind::AngleClose,
+    separators: ListSeparators::COMMA,
+    recovery: |t| {
+        matches!(
+            t.kind,
+            TokenKind::CurlyOpen | TokenKind::CurlyClose | TokenKind::Semicolon | TokenKind::Colon
+        ) || is_keyword(t)
+    },
+};
+
 impl<'c, 't, T: TokenIter<'t>> Parser<'c, 't, T> {
     pub(crate) fn parse_type(&mut self, ident: Token<'t>) {
         self.builder.start_node(NodeKind::Type.into());
@@ -21,21 +35,11 @@ impl<'c, 't, T: TokenIter<'t>> Parser<'c, 't, T> {
                 .start_node_at(checkpoint, NodeKind::TypeArguments.into());
             // has type arguments
 
-            self.consume_trivia();
-
-            if let Some(ident) = self.maybe(TokenKind::Ident) {
-                self.parse_type(ident);
-            }
-
-            self.consume_trivia();
-            while self.consume_maybe(TokenKind::Comma) {
-                self.consume_trivia();
-                if let Some(ident) = self.maybe(TokenKind::Ident) {
-                    self.parse_type(ident);
-                }
-            }
-
-            self.expect(kind(TokenKind::AngleClose), &TRIVIA_NL, |t| false);
+            self.delimited_list(
+                &TYPE_ARGUMENTS,
+                |t| t.kind == TokenKind::Ident,
+                |p, ident| p.parse_type(ident),
+            );
             self.builder.finish_node();
         }
 
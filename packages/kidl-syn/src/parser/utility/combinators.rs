@@ -1,4 +1,9 @@
-use crate::{helpers::ByteOffset, kind::TokenKind, lexer::Token};
+use crate::{
+    helpers::ByteOffset,
+    kind::TokenKind,
+    lexer::Token,
+    parser::{Parser, TokenIter},
+};
 
 use super::error::SyntaxError;
 
@@ -83,3 +88,113 @@ pub fn with_trivia_nl(inner: impl Fn(&Token) -> bool) -> impl Fn(&Token) -> bool
         _ => inner(token),
     }
 }
+
+/// Tokens accepted between the items of a list.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub struct ListSeparators {
+    pub comma: bool,
+    pub semicolon: bool,
+    /// A line break alone is enough to separate two items.
+    pub newline: bool,
+}
+
+impl ListSeparators {
+    pub const COMMA: ListSeparators = ListSeparators {
+        comma: true,
+        semicolon: false,
+        newline: false,
+    };
+
+    pub fn contains(&self, kind: TokenKind) -> bool {
+        match kind {
+            TokenKind::Comma => self.comma,
+            TokenKind::Semicolon => self.semicolon,
+            _ => false,
+        }
+    }
+
+    fn expected(&self) -> Vec<TokenKind> {
+        let mut expected = Vec::new();
+        if self.comma {
+            expected.push(TokenKind::Comma);
+        }
+        if self.semicolon {
+            expected.push(TokenKind::Semicolon);
+        }
+        if self.newline {
+            expected.push(TokenKind::NewLine);
+        }
+        expected
+    }
+}
+
+impl Default for ListSeparators {
+    fn default() -> Self {
+        ListSeparators::COMMA
+    }
+}
+
+/// A list of items between an already consumed opening token and `close`.
+pub struct Delimited {
+    pub close: TokenKind,
+    pub separators: ListSeparators,
+    /// Tokens that can't appear in the list. They end the list when the
+    /// closing token is missing.
+    pub recovery: fn(&Token) -> bool,
+}
+
+impl<'c, 't, T: TokenIter<'t>> Parser<'c, 't, T> {
+    /// Parses a delimited list. Separators and trivia become siblings of
+    /// the item nodes. A missing separator is reported as a lint, the list
+    /// is parsed as if it were there.
+    pub(crate) fn delimited_list(
+        &mut self,
+        list: &Delimited,
+        is_item: impl Fn(&Token) -> bool,
+        mut item: impl FnMut(&mut Self, Token<'t>),
+    ) {
+        let mut expect_separator = false;
+        loop {
+            let line_break = self.consume_trivia();
+            let Some(token) = self.tokens.peek() else {
+                self.error(format!("Expected {:?}, found end of file", list.close));
+                return;
+            };
+
+            match token.kind {
+                kind if kind == list.close => {
+                    self.consume_next();
+                    return;
+                }
+                kind if list.separators.contains(kind) => {
+                    if !expect_separator {
+                        self.error(format!("Unexpected {:?}", kind));
+                    }
+
+                    self.consume_next();
+                    expect_separator = false;
+                }
+                _ if is_item(token) => {
+                    if expect_separator && !(line_break && list.separators.newline) {
+                        self.lint(format!(
+                            "Missing separator, expected one of {:?}",
+                            list.separators.expected()
+                        ));
+                    }
+
+                    let token = self.tokens.next().unwrap();
+                    item(self, token);
+                    expect_separator = true;
+                }
+                kind if (list.recovery)(token) => {
+                    self.error(format!("Expected {:?}, found {:?}", list.close, kind));
+                    return;
+                }
+                _ => {
+                    let token = self.tokens.next().unwrap();
+                    self.unexpected(token);
+                }
+            }
+        }
+    }
+}
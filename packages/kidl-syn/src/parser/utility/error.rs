@@ -2,14 +2,21 @@ use std::{fmt, ops::Range};
 
 use crate::helpers::ByteOffset;
 
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub enum Severity {
+    Error,
+    /// The input is understood, but doesn't follow the recommended style.
+    Lint,
+}
+
 /// Represents the result of unsuccessful tokenization, parsing
 /// or tree validation.
 #[derive(Debug, Clone, PartialEq, Eq, Hash)]
-pub struct SyntaxError(String, Range<ByteOffset>);
+pub struct SyntaxError(String, Range<ByteOffset>, Severity);
 
 impl SyntaxError {
     pub fn new(message: impl Into<String>, range: Range<ByteOffset>) -> Self {
-        Self(message.into(), range)
+        Self(message.into(), range, Severity::Error)
     }
 
     pub fn new_at_offset(message: impl Into<String>, offset: ByteOffset) -> Self {
@@ -19,17 +26,35 @@ impl SyntaxError {
                 start: offset,
                 end: offset,
             },
+            Severity::Error,
         )
     }
 
+    pub fn lint_at_offset(message: impl Into<String>, offset: ByteOffset) -> Self {
+        Self::new_at_offset(message, offset).with_severity(Severity::Lint)
+    }
+
     pub fn range(&self) -> Range<ByteOffset> {
         self.1.clone()
     }
 
+    pub fn severity(&self) -> Severity {
+        self.2
+    }
+
+    pub fn is_lint(&self) -> bool {
+        self.2 == Severity::Lint
+    }
+
     pub fn with_range(mut self, range: Range<ByteOffset>) -> Self {
         self.1 = range;
         self
     }
+
+    pub fn with_severity(mut self, severity: Severity) -> Self {
+        self.2 = severity;
+        self
+    }
 }
 
 impl fmt::Display for SyntaxError {
@@ -1,6 +1,20 @@
-use crate::{kind::TokenKind, lexer::Token};
+use crate::{
+    kind::TokenKind,
+    lexer::{tokenize, Token},
+    source::StrSource,
+};
 
-const KEYWORDS: [&'static str; 1] = ["use"];
+const KEYWORDS: [&'static str; 2] = ["use", "struct"];
 pub fn is_keyword(token: &Token) -> bool {
     token.kind == TokenKind::Ident && KEYWORDS.contains(&token.slice.as_ref())
 }
+
+/// Whether `text` can be used as a name: a single identifier that isn't a
+/// keyword.
+pub fn is_identifier(text: &str) -> bool {
+    let mut tokens = tokenize(StrSource::new(text));
+    match (tokens.next(), tokens.next()) {
+        (Some(token), None) => token.kind == TokenKind::Ident && !is_keyword(&token),
+        _ => false,
+    }
+}
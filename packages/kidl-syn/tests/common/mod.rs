@@ -0,0 +1,23 @@
+use std::path::Path;
+
+use kidl_syn::parser::ListSeparators;
+
+/// Files in a `separators` directory use the field separators their name
+/// lists, e.g. `comma-newline.md`.
+pub fn separators(path: &Path) -> Option<ListSeparators> {
+    let in_separators = path
+        .parent()
+        .and_then(|dir| dir.file_name())
+        .is_some_and(|dir| dir == "separators");
+    if !in_separators {
+        return None;
+    }
+
+    let stem = path.file_stem().unwrap().to_str().unwrap();
+    let names = stem.split('-').collect::<Vec<_>>();
+    Some(ListSeparators {
+        comma: names.contains(&"comma"),
+        semicolon: names.contains(&"semicolon"),
+        newline: names.contains(&"newline"),
+    })
+}
@@ -0,0 +1,132 @@
+use kidl_syn::{
+    ast::{
+        make,
+        schema::{Declaration, Schema},
+        AstNode, SyntaxNode,
+    },
+    parser::parse_str,
+};
+
+fn parse_mut(text: &str) -> Schema {
+    let parsed = parse_str(text);
+    assert!(parsed.errors.is_empty(), "{:?}", parsed.errors);
+    Schema::cast(SyntaxNode::new_root(parsed.schema).clone_for_update()).unwrap()
+}
+
+fn first_struct(schema: &Schema) -> kidl_syn::ast::r#struct::Struct {
+    match schema.declarations().next() {
+        Some(Declaration::Struct(s)) => s,
+        None => panic!("no declarations"),
+    }
+}
+
+/// The edited text must be exactly what the parser produces from it.
+fn assert_round_trip(schema: &Schema, expected: &str) {
+    let text = schema.syntax().to_string();
+    assert_eq!(text, expected);
+
+    let parsed = parse_str(&text);
+    assert!(parsed.errors.is_empty(), "{:?}", parsed.errors);
+    assert_eq!(SyntaxNode::new_root(parsed.schema).to_string(), expected);
+}
+
+#[test]
+fn make_struct() {
+    let s = make::struct_(
+        "A",
+        [
+            make::struct_field("a", make::ty("String", []), false),
+            make::struct_field("b", make::ty("List", [make::ty("B", [])]), true),
+        ],
+    );
+
+    let expected = "struct A {\n    a: String,\n    b?: List<B>,\n}";
+    assert_eq!(s.syntax().to_string(), expected);
+    assert!(parse_str(expected).errors.is_empty());
+}
+
+#[test]
+fn make_use() {
+    let item = make::use_(["webidl", "AbortSignal"]);
+    assert_eq!(item.syntax().to_string(), "use webidl::AbortSignal;");
+
+    let item = make::use_(["./abort.webidl", "AbortSignal"]);
+    assert_eq!(
+        item.syntax().to_string(),
+        "use \"./abort.webidl\"::AbortSignal;"
+    );
+}
+
+#[test]
+fn add_field_keeps_comments() {
+    let schema = parse_mut("// A struct\nstruct A {\n  // first\n  a: B, // trailing\n}\n");
+    first_struct(&schema).add_field(make::struct_field("c", make::ty("D", []), true));
+
+    assert_round_trip(
+        &schema,
+        "// A struct\nstruct A {\n  // first\n  a: B, // trailing\n  c?: D,\n}\n",
+    );
+}
+
+#[test]
+fn add_field_without_trailing_comma() {
+    let schema = parse_mut("struct A {\n    a: B\n}");
+    first_struct(&schema).add_field(make::struct_field("c", make::ty("D", []), false));
+
+    assert_round_trip(&schema, "struct A {\n    a: B,\n    c: D\n}");
+}
+
+#[test]
+fn add_field_to_empty_struct() {
+    let schema = parse_mut("struct A {}\n");
+    first_struct(&schema).add_field(make::struct_field("a", make::ty("B", []), false));
+
+    assert_round_trip(&schema, "struct A {\n    a: B,\n}\n");
+}
+
+#[test]
+fn add_field_to_empty_struct_follows_the_file() {
+    let schema = parse_mut("struct A {}\n\nstruct C {\n\tc: D,\n}\n");
+    first_struct(&schema).add_field(make::struct_field("a", make::ty("B", []), false));
+
+    assert_round_trip(
+        &schema,
+        "struct A {\n\ta: B,\n}\n\nstruct C {\n\tc: D,\n}\n",
+    );
+}
+
+#[test]
+fn remove_field() {
+    let schema = parse_mut("struct A {\n    a: B,\n    c: D,\n}");
+    first_struct(&schema).fields().next().unwrap().remove();
+
+    assert_round_trip(&schema, "struct A {\n    c: D,\n}");
+}
+
+#[test]
+fn add_use() {
+    let schema = parse_mut("use a::B;\n\nstruct A {\n    b: B,\n}\n");
+    schema.add_use(make::use_(["c", "D"]));
+
+    assert_round_trip(
+        &schema,
+        "use a::B;\nuse c::D;\n\nstruct A {\n    b: B,\n}\n",
+    );
+
+    let schema = parse_mut("struct A {}\n");
+    schema.add_use(make::use_(["c", "D"]));
+
+    assert_round_trip(&schema, "use c::D;\n\nstruct A {}\n");
+}
+
+#[test]
+fn rename_ident() {
+    use kidl_syn::ast::helpers::HasName;
+
+    let schema = parse_mut("struct A {\n    a: B,\n}\n");
+    let s = first_struct(&schema);
+    s.name().unwrap().rename("Renamed");
+    s.fields().next().unwrap().name().unwrap().rename("field");
+
+    assert_round_trip(&schema, "struct Renamed {\n    field: B,\n}\n");
+}
@@ -0,0 +1,37 @@
+mod common;
+
+use std::path::Path;
+
+use kidl_syn::format::{format_str, FormatConfig};
+use snapdown::{run_test, Syntax};
+
+fn config(path: &Path) -> FormatConfig {
+    match common::separators(path) {
+        Some(field_separators) => FormatConfig {
+            field_separators,
+            ..Default::default()
+        },
+        None => FormatConfig::default(),
+    }
+}
+
+fn format(path: &Path) -> datatest_stable::Result<()> {
+    let config = config(path);
+    run_test::<Syntax, _>(path, |blocks| {
+        blocks.chunks_mut(2).for_each(|test| {
+            let input = &test[0];
+            let output = &test[1];
+
+            let formatted = format_str(input.text, &config).unwrap();
+            assert_eq!(
+                format_str(&formatted, &config).unwrap(),
+                formatted,
+                "formatting is not idempotent"
+            );
+
+            output.result.set(Some(formatted));
+        });
+    })
+}
+
+datatest_stable::harness!(format, "tests/format", r".*");
@@ -0,0 +1,57 @@
+use kidl_syn::{
+    ast::{
+        helpers::{doc_comment, token_at_offset},
+        SyntaxNode,
+    },
+    helpers::ByteOffset,
+    parser::{is_identifier, parse_str},
+};
+
+fn token_text(text: &str, offset: usize) -> Option<String> {
+    let root = SyntaxNode::new_root(parse_str(text).schema);
+    token_at_offset(&root, ByteOffset(offset)).map(|t| t.text().to_owned())
+}
+
+#[test]
+fn token_at_offset_prefers_names() {
+    let text = "struct A {\n    a: List<B>,\n}\n";
+
+    assert_eq!(token_text(text, 0).as_deref(), Some("struct"));
+    assert_eq!(token_text(text, 7).as_deref(), Some("A"));
+    // Right after a name
+    assert_eq!(token_text(text, 8).as_deref(), Some("A"));
+    // Between `<` and `B`
+    assert_eq!(token_text(text, 23).as_deref(), Some("B"));
+    assert_eq!(token_text(text, 22).as_deref(), Some("List"));
+    assert_eq!(token_text(text, text.len() + 1), None);
+}
+
+#[test]
+fn identifiers() {
+    assert!(is_identifier("AbortSignal"));
+    assert!(is_identifier("a_1"));
+    assert!(!is_identifier(""));
+    assert!(!is_identifier("struct"));
+    assert!(!is_identifier("a b"));
+    assert!(!is_identifier("1a"));
+    assert!(!is_identifier("a::b"));
+}
+
+fn doc_of(text: &str, name: &str) -> Option<String> {
+    let root = SyntaxNode::new_root(parse_str(text).schema);
+    let token = root
+        .descendants_with_tokens()
+        .filter_map(|e| e.into_token())
+        .find(|t| t.text() == name)?;
+    doc_comment(&token.parent()?)
+}
+
+#[test]
+fn doc_comments() {
+    let text = "use m::B;\n\n// Not a doc\n\n// A struct\n//   indented\nstruct A {\n    // First\n    a: B, // trailing\n    b: B,\n    /// Third\n    c: B,\n}\n";
+
+    assert_eq!(doc_of(text, "A").as_deref(), Some("A struct\n  indented"));
+    assert_eq!(doc_of(text, "a").as_deref(), Some("First"));
+    assert_eq!(doc_of(text, "b"), None);
+    assert_eq!(doc_of(text, "c").as_deref(), Some("Third"));
+}
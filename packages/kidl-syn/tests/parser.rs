@@ -1,15 +1,25 @@
+mod common;
+
 use std::path::Path;
 
-use kidl_syn::debug::DebugNodePrinter;
+use kidl_syn::{debug::DebugNodePrinter, parser::ParseOptions};
 use snapdown::{run_test, Syntax};
 
+fn options(path: &Path) -> ParseOptions {
+    match common::separators(path) {
+        Some(field_separators) => ParseOptions { field_separators },
+        None => ParseOptions::default(),
+    }
+}
+
 fn parser(path: &Path) -> datatest_stable::Result<()> {
+    let options = options(path);
     run_test::<Syntax, _>(path, |blocks| {
         blocks.chunks_mut(2).for_each(|test| {
             let input = &test[0];
             let output = &test[1];
 
-            let parsed = kidl_syn::parser::parse_str(input.text);
+            let parsed = kidl_syn::parser::parse_str_with_options(input.text, options);
             output.result.set(Some(format!(
                 "{:#?} {:#?}",
                 DebugNodePrinter(parsed.schema),
@@ -0,0 +1,58 @@
+use kidl_syn::{ast::SyntaxNode, lexer::tokenize, parser::parse_str, source::StrSource};
+use proptest::prelude::*;
+
+/// Source text that looks like a schema: keywords, punctuation and trivia
+/// in random order, so the parser gets past the first token more often
+/// than with arbitrary strings.
+fn schema_like() -> impl Strategy<Value = String> {
+    let fragment = prop_oneof![
+        Just("struct".to_owned()),
+        Just("use".to_owned()),
+        "[a-zA-Z_][a-zA-Z0-9_]{0,8}",
+        "[0-9]{1,4}",
+        "\"[^\"\n]{0,8}\"?",
+        "//[^\n]{0,12}\n?",
+        "[{}<>\\[\\]():;,.#?=/]",
+        "[ \t\n]{1,3}",
+        any::<char>().prop_map(String::from),
+    ];
+
+    prop::collection::vec(fragment, 0..64).prop_map(|f| f.concat())
+}
+
+fn assert_lossless(text: &str) {
+    let tokens = tokenize(StrSource::new(text))
+        .map(|t| t.slice.into_owned())
+        .collect::<String>();
+    assert_eq!(tokens, text);
+
+    let parsed = parse_str(text);
+    assert_eq!(SyntaxNode::new_root(parsed.schema).to_string(), text);
+}
+
+proptest! {
+    #![proptest_config(ProptestConfig::with_cases(512))]
+
+    #[test]
+    fn arbitrary_text_round_trips(text in any::<String>()) {
+        assert_lossless(&text);
+    }
+
+    #[test]
+    fn schema_like_text_round_trips(text in schema_like()) {
+        assert_lossless(&text);
+    }
+}
+
+#[test]
+fn unknown_characters_round_trip() {
+    for text in [
+        "/",
+        "a / b",
+        "struct A { a: B / }",
+        "use a::€;",
+        "\u{0}\u{200b}",
+    ] {
+        assert_lossless(text);
+    }
+}
@@ -0,0 +1,109 @@
+use crate::{ParseError, Span};
+
+#[derive(Debug, Clone, PartialEq, Eq, Default)]
+pub struct Definitions {
+    pub definitions: Vec<Definition>,
+    pub errors: Vec<ParseError>,
+}
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum Definition {
+    Interface(Interface),
+    Dictionary(Dictionary),
+    Enum(Enum),
+    Typedef(Typedef),
+}
+
+impl Definition {
+    pub fn name(&self) -> &Ident {
+        match self {
+            Definition::Interface(d) => &d.name,
+            Definition::Dictionary(d) => &d.name,
+            Definition::Enum(d) => &d.name,
+            Definition::Typedef(d) => &d.name,
+        }
+    }
+}
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Ident {
+    /// The name without the escaping underscore, e.g. `any` for `_any`.
+    pub text: String,
+    pub span: Span,
+}
+
+/// An interface or an interface mixin.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Interface {
+    pub name: Ident,
+    pub partial: bool,
+    pub mixin: bool,
+    pub inherits: Option<Ident>,
+    pub attributes: Vec<Attribute>,
+    pub span: Span,
+}
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Attribute {
+    pub name: Ident,
+    pub ty: Type,
+    pub readonly: bool,
+    pub span: Span,
+}
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Dictionary {
+    pub name: Ident,
+    pub partial: bool,
+    pub inherits: Option<Ident>,
+    pub members: Vec<DictionaryMember>,
+    pub span: Span,
+}
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct DictionaryMember {
+    pub name: Ident,
+    pub ty: Type,
+    pub required: bool,
+    pub has_default: bool,
+    pub span: Span,
+}
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Enum {
+    pub name: Ident,
+    pub values: Vec<EnumValue>,
+    pub span: Span,
+}
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct EnumValue {
+    /// The value without quotes.
+    pub text: String,
+    pub span: Span,
+}
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Typedef {
+    pub name: Ident,
+    pub ty: Type,
+    pub span: Span,
+}
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum TypeKind {
+    /// A named type. Multi-word names are joined with a single space,
+    /// e.g. `unsigned long long`.
+    Named {
+        name: String,
+        args: Vec<Type>,
+    },
+    Union(Vec<Type>),
+}
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Type {
+    pub kind: TypeKind,
+    pub nullable: bool,
+    pub span: Span,
+}
@@ -0,0 +1,108 @@
+use crate::Span;
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum TokenKind {
+    Ident,
+    Number,
+    String,
+    /// A single punctuation character.
+    Punct(char),
+    /// `...`
+    Ellipsis,
+    /// Anything else, e.g. preprocessor lines in some browser sources.
+    Other,
+}
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Token {
+    pub kind: TokenKind,
+    pub span: Span,
+}
+
+struct Lexer<'a> {
+    text: &'a str,
+    offset: usize,
+}
+
+impl<'a> Lexer<'a> {
+    fn rest(&self) -> &'a str {
+        &self.text[self.offset..]
+    }
+
+    fn bump_while(&mut self, predicate: impl Fn(char) -> bool) {
+        let len = self
+            .rest()
+            .find(|c| !predicate(c))
+            .unwrap_or(self.rest().len());
+        self.offset += len;
+    }
+
+    /// Moves past `pattern`, or to the end of the text if it's missing.
+    fn bump_past(&mut self, pattern: &str) {
+        self.offset = match self.rest().find(pattern) {
+            Some(index) => self.offset + index + pattern.len(),
+            None => self.text.len(),
+        };
+    }
+}
+
+fn is_ident_char(c: char) -> bool {
+    c.is_ascii_alphanumeric() || c == '_' || c == '-'
+}
+
+/// Splits `text` into tokens, skipping whitespace and comments.
+pub fn tokenize(text: &str) -> Vec<Token> {
+    let mut lexer = Lexer { text, offset: 0 };
+    let mut tokens = Vec::new();
+
+    while let Some(c) = lexer.rest().chars().next() {
+        let start = lexer.offset;
+        let rest = lexer.rest();
+        lexer.offset += c.len_utf8();
+
+        let kind = match c {
+            c if c.is_whitespace() => continue,
+            '/' if rest.starts_with("//") => {
+                lexer.bump_while(|c| c != '\n');
+                continue;
+            }
+            '/' if rest.starts_with("/*") => {
+                lexer.bump_past("*/");
+                continue;
+            }
+            '#' => {
+                lexer.bump_while(|c| c != '\n');
+                TokenKind::Other
+            }
+            '"' => {
+                lexer.bump_past("\"");
+                TokenKind::String
+            }
+            '.' if rest.starts_with("...") => {
+                lexer.offset += 2;
+                TokenKind::Ellipsis
+            }
+            '-' if rest[1..].starts_with(|c: char| c.is_ascii_digit()) => {
+                lexer.bump_while(|c| c.is_ascii_alphanumeric() || c == '.');
+                TokenKind::Number
+            }
+            c if c.is_ascii_digit() => {
+                lexer.bump_while(|c| c.is_ascii_alphanumeric() || c == '.');
+                TokenKind::Number
+            }
+            c if c.is_ascii_alphabetic() || c == '_' || c == '-' => {
+                lexer.bump_while(is_ident_char);
+                TokenKind::Ident
+            }
+            c if c.is_ascii_punctuation() => TokenKind::Punct(c),
+            _ => TokenKind::Other,
+        };
+
+        tokens.push(Token {
+            kind,
+            span: start..lexer.offset,
+        });
+    }
+
+    tokens
+}
@@ -0,0 +1,29 @@
+//! A WebIDL parser for importing WebIDL declarations into KIDL schemas.
+//!
+//! Only the parts that map onto KIDL are kept: interfaces (with their
+//! attributes), dictionaries, enums and typedefs. Operations, constants,
+//! callbacks, namespaces and other definitions are skipped. The parser
+//! never fails, syntax errors are collected and parsing resumes at the next
+//! definition.
+
+pub mod ast;
+mod lexer;
+mod parser;
+
+use std::{fmt, ops::Range};
+
+pub use parser::parse;
+
+pub type Span = Range<usize>;
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct ParseError {
+    pub message: String,
+    pub span: Span,
+}
+
+impl fmt::Display for ParseError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        self.message.fmt(f)
+    }
+}
@@ -0,0 +1,467 @@
+use crate::{
+    ast::{
+        Attribute, Definition, Definitions, Dictionary, DictionaryMember, Enum, EnumValue, Ident,
+        Interface, Type, TypeKind, Typedef,
+    },
+    lexer::{tokenize, Token, TokenKind},
+    ParseError, Span,
+};
+
+/// Marks that an error was reported and the caller should recover.
+struct Failed;
+
+type Result<T> = std::result::Result<T, Failed>;
+
+struct Parser<'a> {
+    text: &'a str,
+    tokens: Vec<Token>,
+    pos: usize,
+    errors: Vec<ParseError>,
+}
+
+pub fn parse(text: &str) -> Definitions {
+    let mut parser = Parser {
+        text,
+        tokens: tokenize(text),
+        pos: 0,
+        errors: Vec::new(),
+    };
+
+    let mut definitions = Vec::new();
+    while parser.peek().is_some() {
+        let start = parser.pos;
+        match parser.definition() {
+            Ok(Some(definition)) => definitions.push(definition),
+            Ok(None) => {}
+            Err(Failed) => {
+                parser.pos = start;
+                parser.skip_statement();
+            }
+        }
+    }
+
+    Definitions {
+        definitions,
+        errors: parser.errors,
+    }
+}
+
+impl<'a> Parser<'a> {
+    fn peek(&self) -> Option<&Token> {
+        self.tokens.get(self.pos)
+    }
+
+    fn peek_text(&self) -> &'a str {
+        self.peek().map_or("", |t| &self.text[t.span.clone()])
+    }
+
+    fn at_punct(&self, c: char) -> bool {
+        self.peek().is_some_and(|t| t.kind == TokenKind::Punct(c))
+    }
+
+    fn at_keyword(&self, keyword: &str) -> bool {
+        self.peek()
+            .is_some_and(|t| t.kind == TokenKind::Ident && self.peek_text() == keyword)
+    }
+
+    fn bump(&mut self) -> Option<Token> {
+        let token = self.tokens.get(self.pos).cloned();
+        self.pos += 1;
+        token
+    }
+
+    fn eat_punct(&mut self, c: char) -> bool {
+        let matches = self.at_punct(c);
+        if matches {
+            self.pos += 1;
+        }
+        matches
+    }
+
+    fn eat_keyword(&mut self, keyword: &str) -> bool {
+        let matches = self.at_keyword(keyword);
+        if matches {
+            self.pos += 1;
+        }
+        matches
+    }
+
+    /// End of the previous token.
+    fn last_end(&self) -> usize {
+        self.pos
+            .checked_sub(1)
+            .and_then(|i| self.tokens.get(i))
+            .map_or(0, |t| t.span.end)
+    }
+
+    fn current_span(&self) -> Span {
+        match self.peek() {
+            Some(token) => token.span.clone(),
+            None => self.text.len()..self.text.len(),
+        }
+    }
+
+    fn error<T>(&mut self, expected: &str) -> Result<T> {
+        let found = match self.peek() {
+            Some(_) => format!("`{}`", self.peek_text()),
+            None => "end of file".to_owned(),
+        };
+
+        self.errors.push(ParseError {
+            message: format!("Expected {}, found {}", expected, found),
+            span: self.current_span(),
+        });
+        Err(Failed)
+    }
+
+    fn expect_punct(&mut self, c: char) -> Result<()> {
+        match self.eat_punct(c) {
+            true => Ok(()),
+            false => self.error(&format!("`{}`", c)),
+        }
+    }
+
+    fn expect_ident(&mut self) -> Result<Ident> {
+        match self.peek() {
+            Some(token) if token.kind == TokenKind::Ident => {
+                let text = self.peek_text();
+                let span = token.span.clone();
+                self.pos += 1;
+                Ok(Ident {
+                    text: text.strip_prefix('_').unwrap_or(text).to_owned(),
+                    span,
+                })
+            }
+            _ => self.error("identifier"),
+        }
+    }
+
+    /// Skips tokens up to and including the next `;` outside of brackets.
+    fn skip_statement(&mut self) {
+        let mut depth = 0usize;
+        while let Some(token) = self.bump() {
+            match token.kind {
+                TokenKind::Punct('{' | '(' | '[') => depth += 1,
+                TokenKind::Punct('}' | ')' | ']') => depth = depth.saturating_sub(1),
+                TokenKind::Punct(';') if depth == 0 => return,
+                _ => {}
+            }
+        }
+    }
+
+    /// Like [`Parser::skip_statement`], but stops in front of the `}` that
+    /// closes the enclosing definition.
+    fn skip_member(&mut self) {
+        let mut depth = 0usize;
+        while let Some(token) = self.peek() {
+            match token.kind {
+                TokenKind::Punct('}') if depth == 0 => return,
+                TokenKind::Punct('{' | '(' | '[') => depth += 1,
+                TokenKind::Punct('}' | ')' | ']') => depth = depth.saturating_sub(1),
+                TokenKind::Punct(';') if depth == 0 => {
+                    self.pos += 1;
+                    return;
+                }
+                _ => {}
+            }
+            self.pos += 1;
+        }
+    }
+
+    /// Skips `[...]` extended attributes, which don't affect the model.
+    fn skip_extended_attributes(&mut self) {
+        while self.at_punct('[') {
+            let mut depth = 0usize;
+            while let Some(token) = self.bump() {
+                match token.kind {
+                    TokenKind::Punct('[') => depth += 1,
+                    TokenKind::Punct(']') => {
+                        depth -= 1;
+                        if depth == 0 {
+                            break;
+                        }
+                    }
+                    _ => {}
+                }
+            }
+        }
+    }
+
+    fn definition(&mut self) -> Result<Option<Definition>> {
+        self.skip_extended_attributes();
+        let start = self.current_span().start;
+        let partial = self.eat_keyword("partial");
+
+        let definition = match self.peek_text() {
+            "interface" => {
+                self.pos += 1;
+                let mixin = self.eat_keyword("mixin");
+                Definition::Interface(self.interface(start, partial, mixin)?)
+            }
+            "dictionary" => {
+                self.pos += 1;
+                Definition::Dictionary(self.dictionary(start, partial)?)
+            }
+            "enum" => {
+                self.pos += 1;
+                Definition::Enum(self.enumeration(start)?)
+            }
+            "typedef" => {
+                self.pos += 1;
+                Definition::Typedef(self.typedef(start)?)
+            }
+            "callback" | "namespace" => {
+                self.skip_statement();
+                return Ok(None);
+            }
+            // `A includes B;`
+            _ if self.tokens.get(self.pos + 1).is_some_and(|t| {
+                t.kind == TokenKind::Ident && &self.text[t.span.clone()] == "includes"
+            }) =>
+            {
+                self.skip_statement();
+                return Ok(None);
+            }
+            _ => return self.error("a definition"),
+        };
+
+        Ok(Some(definition))
+    }
+
+    fn inheritance(&mut self) -> Result<Option<Ident>> {
+        match self.eat_punct(':') {
+            true => Ok(Some(self.expect_ident()?)),
+            false => Ok(None),
+        }
+    }
+
+    fn interface(&mut self, start: usize, partial: bool, mixin: bool) -> Result<Interface> {
+        let name = self.expect_ident()?;
+        let inherits = self.inheritance()?;
+        self.expect_punct('{')?;
+
+        let mut attributes = Vec::new();
+        while !self.eat_punct('}') {
+            if self.peek().is_none() {
+                return self.error("`}`");
+            }
+
+            let member = self.pos;
+            match self.attribute() {
+                Ok(Some(attribute)) => attributes.push(attribute),
+                Ok(None) | Err(Failed) => {
+                    self.pos = member;
+                    self.skip_member();
+                }
+            }
+        }
+        self.expect_punct(';')?;
+
+        Ok(Interface {
+            name,
+            partial,
+            mixin,
+            inherits,
+            attributes,
+            span: start..self.last_end(),
+        })
+    }
+
+    /// Parses an attribute, or returns `None` for other kinds of members.
+    fn attribute(&mut self) -> Result<Option<Attribute>> {
+        self.skip_extended_attributes();
+        let start = self.current_span().start;
+        while self.eat_keyword("static")
+            || self.eat_keyword("stringifier")
+            || self.eat_keyword("inherit")
+        {}
+
+        let readonly = self.eat_keyword("readonly");
+        if !self.eat_keyword("attribute") {
+            return Ok(None);
+        }
+
+        let ty = self.ty()?;
+        let name = self.expect_ident()?;
+        self.expect_punct(';')?;
+
+        Ok(Some(Attribute {
+            name,
+            ty,
+            readonly,
+            span: start..self.last_end(),
+        }))
+    }
+
+    fn dictionary(&mut self, start: usize, partial: bool) -> Result<Dictionary> {
+        let name = self.expect_ident()?;
+        let inherits = self.inheritance()?;
+        self.expect_punct('{')?;
+
+        let mut members = Vec::new();
+        while !self.eat_punct('}') {
+            if self.peek().is_none() {
+                return self.error("`}`");
+            }
+
+            let member = self.pos;
+            match self.dictionary_member() {
+                Ok(member) => members.push(member),
+                Err(Failed) => {
+                    self.pos = member;
+                    self.skip_member();
+                }
+            }
+        }
+        self.expect_punct(';')?;
+
+        Ok(Dictionary {
+            name,
+            partial,
+            inherits,
+            members,
+            span: start..self.last_end(),
+        })
+    }
+
+    fn dictionary_member(&mut self) -> Result<DictionaryMember> {
+        self.skip_extended_attributes();
+        let start = self.current_span().start;
+        let required = self.eat_keyword("required");
+        let ty = self.ty()?;
+        let name = self.expect_ident()?;
+
+        let has_default = self.eat_punct('=');
+        if has_default {
+            self.default_value()?;
+        }
+        self.expect_punct(';')?;
+
+        Ok(DictionaryMember {
+            name,
+            ty,
+            required,
+            has_default,
+            span: start..self.last_end(),
+        })
+    }
+
+    /// Skips a default value: a literal, `[]`, `{}` or `null`.
+    fn default_value(&mut self) -> Result<()> {
+        if self.eat_punct('[') {
+            return self.expect_punct(']');
+        }
+        if self.eat_punct('{') {
+            return self.expect_punct('}');
+        }
+
+        match self.peek().map(|t| t.kind) {
+            Some(TokenKind::Ident | TokenKind::Number | TokenKind::String) => {
+                self.pos += 1;
+                Ok(())
+            }
+            _ => self.error("a default value"),
+        }
+    }
+
+    fn enumeration(&mut self, start: usize) -> Result<Enum> {
+        let name = self.expect_ident()?;
+        self.expect_punct('{')?;
+
+        let mut values = Vec::new();
+        while !self.eat_punct('}') {
+            match self.peek() {
+                Some(token) if token.kind == TokenKind::String => {
+                    let span = token.span.clone();
+                    let text = self.peek_text();
+                    values.push(EnumValue {
+                        text: text.trim_matches('"').to_owned(),
+                        span,
+                    });
+                    self.pos += 1;
+                }
+                _ => return self.error("a string"),
+            }
+
+            if !self.eat_punct(',') && !self.at_punct('}') {
+                return self.error("`,` or `}`");
+            }
+        }
+        self.expect_punct(';')?;
+
+        Ok(Enum {
+            name,
+            values,
+            span: start..self.last_end(),
+        })
+    }
+
+    fn typedef(&mut self, start: usize) -> Result<Typedef> {
+        let ty = self.ty()?;
+        let name = self.expect_ident()?;
+        self.expect_punct(';')?;
+
+        Ok(Typedef {
+            name,
+            ty,
+            span: start..self.last_end(),
+        })
+    }
+
+    fn ty(&mut self) -> Result<Type> {
+        self.skip_extended_attributes();
+        let start = self.current_span().start;
+
+        let kind = if self.eat_punct('(') {
+            let mut members = vec![self.ty()?];
+            while self.eat_keyword("or") {
+                members.push(self.ty()?);
+            }
+            self.expect_punct(')')?;
+            TypeKind::Union(members)
+        } else {
+            let name = self.type_name()?;
+            let mut args = Vec::new();
+            if self.eat_punct('<') {
+                args.push(self.ty()?);
+                while self.eat_punct(',') {
+                    args.push(self.ty()?);
+                }
+                self.expect_punct('>')?;
+            }
+            TypeKind::Named { name, args }
+        };
+
+        let nullable = self.eat_punct('?');
+        Ok(Type {
+            kind,
+            nullable,
+            span: start..self.last_end(),
+        })
+    }
+
+    /// A type name, joining multi-word primitive names like
+    /// `unrestricted double` or `unsigned long long`.
+    fn type_name(&mut self) -> Result<String> {
+        let mut words = Vec::new();
+        for prefix in ["unsigned", "unrestricted"] {
+            if self.eat_keyword(prefix) {
+                words.push(prefix);
+            }
+        }
+
+        if self.eat_keyword("long") {
+            words.push("long");
+            if self.eat_keyword("long") {
+                words.push("long");
+            }
+        } else if words.is_empty() {
+            return Ok(self.expect_ident()?.text);
+        } else {
+            words.push(self.peek_text());
+            self.expect_ident()?;
+        }
+
+        Ok(words.join(" "))
+    }
+}
@@ -0,0 +1,139 @@
+use kidl_webidl::{
+    ast::{Definition, TypeKind},
+    parse,
+};
+
+const ABORT_SIGNAL: &str = r#"
+/* This Source Code Form is subject to the terms of the Mozilla Public
+ * License, v. 2.0. */
+
+[Exposed=*]
+interface AbortSignal : EventTarget {
+  [NewObject] static AbortSignal abort(optional any reason);
+  [NewObject] static AbortSignal _any(sequence<AbortSignal> signals);
+
+  readonly attribute boolean aborted;
+  readonly attribute any reason;
+  [Throws] undefined throwIfAborted();
+
+  attribute EventHandler onabort;
+};
+"#;
+
+fn named(kind: &TypeKind) -> (&str, usize) {
+    match kind {
+        TypeKind::Named { name, args } => (name.as_str(), args.len()),
+        TypeKind::Union(_) => ("union", 0),
+    }
+}
+
+#[test]
+fn interface_attributes() {
+    let parsed = parse(ABORT_SIGNAL);
+    assert_eq!(parsed.errors, vec![]);
+
+    let [Definition::Interface(interface)] = parsed.definitions.as_slice() else {
+        panic!("{:#?}", parsed.definitions);
+    };
+
+    assert_eq!(interface.name.text, "AbortSignal");
+    assert_eq!(interface.inherits.as_ref().unwrap().text, "EventTarget");
+
+    let attributes = interface
+        .attributes
+        .iter()
+        .map(|a| (a.name.text.as_str(), named(&a.ty.kind).0, a.readonly))
+        .collect::<Vec<_>>();
+    assert_eq!(
+        attributes,
+        vec![
+            ("aborted", "boolean", true),
+            ("reason", "any", true),
+            ("onabort", "EventHandler", false),
+        ]
+    );
+
+    let aborted = &interface.attributes[0];
+    assert_eq!(
+        &ABORT_SIGNAL[aborted.span.clone()],
+        "readonly attribute boolean aborted;"
+    );
+    assert_eq!(&ABORT_SIGNAL[aborted.name.span.clone()], "aborted");
+}
+
+#[test]
+fn dictionaries_enums_and_typedefs() {
+    let parsed = parse(
+        r#"
+        dictionary Options : Base {
+          required unsigned long long size;
+          DOMString? label = null;
+          sequence<(long or DOMString)> items = [];
+          record<DOMString, double> weights;
+        };
+        enum Mode { "fast", "slow", };
+        typedef (Options or Mode)? Config;
+        callback Listener = undefined (Event event);
+        Window includes Listener;
+        "#,
+    );
+    assert_eq!(parsed.errors, vec![]);
+    assert_eq!(parsed.definitions.len(), 3);
+
+    let Definition::Dictionary(options) = &parsed.definitions[0] else {
+        panic!()
+    };
+    let members = options
+        .members
+        .iter()
+        .map(|m| {
+            let (name, args) = named(&m.ty.kind);
+            (m.name.text.as_str(), name, args, m.required, m.ty.nullable)
+        })
+        .collect::<Vec<_>>();
+    assert_eq!(
+        members,
+        vec![
+            ("size", "unsigned long long", 0, true, false),
+            ("label", "DOMString", 0, false, true),
+            ("items", "sequence", 1, false, false),
+            ("weights", "record", 2, false, false),
+        ]
+    );
+
+    let Definition::Enum(mode) = &parsed.definitions[1] else {
+        panic!()
+    };
+    let values = mode
+        .values
+        .iter()
+        .map(|v| v.text.as_str())
+        .collect::<Vec<_>>();
+    assert_eq!(values, ["fast", "slow"]);
+
+    let Definition::Typedef(config) = &parsed.definitions[2] else {
+        panic!()
+    };
+    assert!(matches!(&config.ty.kind, TypeKind::Union(members) if members.len() == 2));
+    assert!(config.ty.nullable);
+}
+
+#[test]
+fn recovers_after_errors() {
+    let parsed = parse(
+        r#"
+        dictionary Broken { long };
+        interface Fine { attribute long value; };
+        garbage;
+        enum E { "a" };
+        "#,
+    );
+
+    assert_eq!(parsed.errors.len(), 2, "{:?}", parsed.errors);
+    let names = parsed
+        .definitions
+        .iter()
+        .map(|d| d.name().text.as_str())
+        .collect::<Vec<_>>();
+    assert_eq!(names, ["Broken", "Fine", "E"]);
+}